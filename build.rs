@@ -55,7 +55,8 @@ fn main() {
   //process bmps
   for entry in read_dir("./bmps").unwrap() {
     let path = entry.unwrap().path();
-    if path.is_dir() {
+    //icons are just bmps, not fonts
+    if path.is_dir() && !path.ends_with("icons") {
       font_chars_to_alphas(path.to_str().unwrap());
     }
   }
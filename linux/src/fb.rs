@@ -9,15 +9,18 @@ use libc::{ ioctl, mmap, munmap, c_ulong, c_int };
 //https://github.com/torvalds/linux/blob/master/include/uapi/linux/fb.h
 const FBIOGET_VSCREENINFO: c_ulong = 0x4600;
 const FBIOGET_FSCREENINFO: c_ulong = 0x4602;
+const FBIOPAN_DISPLAY: c_ulong = 0x4606;
+//_IOW('F', 0x20, __u32)
+const FBIO_WAITFORVSYNC: c_ulong = 0x40044620;
 
 //https://www.kernel.org/doc/html/latest/fb/api.html
 
 #[derive(Default)]
 #[repr(C)]
 pub struct FB_BITFIELD {
-  offset: u32,
-  length: u32,
-  msb_right: u32,
+  pub offset: u32,
+  pub length: u32,
+  pub msb_right: u32,
 }
 
 #[derive(Default)]
@@ -74,10 +77,17 @@ pub struct FB_FIX_SCREENINFO {
 }
 
 pub struct Framebuffer {
+  file: File,
   pointer: *mut libc::c_void,
   pub var_screen_info: FB_VAR_SCREENINFO,
   pub fix_screen_info: FB_FIX_SCREENINFO,
   size: usize,
+  //if on, frames are written to the half of the virtual screen not being shown, which is then panned to
+  double_buffered: bool,
+  vsync: bool, //wait for vsync before panning
+  back: usize, //0 or 1, the half not being shown
+  last_ranges: Option<Vec<(usize, usize)>>, //what was written to the other half last frame, None if everything
+  headless: bool, //a regular file, which can't be panned, so panning always works
 }
 
 impl Framebuffer {
@@ -87,17 +97,54 @@ impl Framebuffer {
     let fi = Framebuffer::get_fscreeninfo(file.as_raw_fd())?;
     //then mmap or something
     let size = vi.yres_virtual * fi.line_length * (vi.bits_per_pixel / 8);
+    Framebuffer::map(file, vi, fi, size as usize)
+  }
+
+  //a regular file (created if needed) instead of a framebuffer device, eg: for tests
+  pub fn open_headless(path: &str, width: u32, height: u32, bytes_per_pixel: u32) -> Result<Self, ()> {
+    Framebuffer::open_headless_virtual(path, width, height, height, bytes_per_pixel)
+  }
+
+  //with a virtual screen taller than the screen, so double buffering can be used
+  pub fn open_headless_virtual(path: &str, width: u32, height: u32, virtual_height: u32, bytes_per_pixel: u32) -> Result<Self, ()> {
+    let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path).map_err(|_| ())?;
+    let vi = FB_VAR_SCREENINFO {
+      xres: width,
+      yres: height,
+      xres_virtual: width,
+      yres_virtual: virtual_height,
+      bits_per_pixel: bytes_per_pixel * 8,
+      ..Default::default()
+    };
+    let fi = FB_FIX_SCREENINFO {
+      line_length: width * bytes_per_pixel,
+      ..Default::default()
+    };
+    let size = (fi.line_length * virtual_height) as usize;
+    file.set_len(size as u64).map_err(|_| ())?;
+    let mut framebuffer = Framebuffer::map(file, vi, fi, size)?;
+    framebuffer.headless = true;
+    Ok(framebuffer)
+  }
+
+  fn map(file: File, vi: FB_VAR_SCREENINFO, fi: FB_FIX_SCREENINFO, size: usize) -> Result<Self, ()> {
     let pointer = unsafe {
-      mmap(ptr::null_mut(), size.try_into().unwrap(), libc::PROT_READ | libc::PROT_WRITE, libc::MAP_SHARED, file.as_raw_fd(), 0)
+      mmap(ptr::null_mut(), size, libc::PROT_READ | libc::PROT_WRITE, libc::MAP_SHARED, file.as_raw_fd(), 0)
     };
     if pointer == libc::MAP_FAILED {
       return Err(());
     }
     Ok(Self {
+      file,
       pointer,
       var_screen_info: vi,
       fix_screen_info: fi,
-      size: size as usize,
+      size,
+      double_buffered: false,
+      vsync: false,
+      back: 0,
+      last_ranges: None,
+      headless: false,
     })
   }
   
@@ -129,10 +176,96 @@ impl Framebuffer {
     }
   }
 
-  pub fn write_frame(&mut self, frame: &[u8]) {
+  //needs the virtual screen to be at least twice as tall as the screen, and the driver to support panning.
+  //returns whether double buffering is on
+  pub fn enable_double_buffering(&mut self, vsync: bool) -> bool {
+    if self.var_screen_info.yres_virtual < self.var_screen_info.yres * 2 {
+      return false;
+    }
+    //panning to where it already is tells us if panning works
+    self.var_screen_info.yoffset = 0;
+    if self.pan().is_err() {
+      return false;
+    }
+    self.double_buffered = true;
+    self.vsync = vsync;
+    self.back = 1;
+    self.last_ranges = None;
+    true
+  }
+
+  fn pan(&mut self) -> Result<(), ()> {
+    if self.headless {
+      return Ok(());
+    }
+    let result = unsafe {
+      ioctl(self.file.as_raw_fd(), FBIOPAN_DISPLAY.try_into().unwrap(), &mut self.var_screen_info)
+    };
+    if result != -1 {
+      Ok(())
+    } else {
+      Err(())
+    }
+  }
+
+  //not all drivers support it, in which case it just doesn't wait
+  fn wait_for_vsync(&self) {
+    let mut arg: u32 = 0;
+    unsafe {
+      ioctl(self.file.as_raw_fd(), FBIO_WAITFORVSYNC.try_into().unwrap(), &mut arg);
+    }
+  }
+
+  //`len` bytes of the frame starting at `start`, to `offset` bytes after that in the framebuffer
+  fn copy(&mut self, frame: &[u8], offset: usize, start: usize, len: usize) {
+    if offset + start + len > self.size {
+      return;
+    }
+    let region = &frame[start..(start + len)];
     unsafe {
-      ptr::copy_nonoverlapping(frame.as_ptr(), self.pointer as *mut u8, frame.len());
+      ptr::copy_nonoverlapping(region.as_ptr(), (self.pointer as *mut u8).add(offset + start), len);
+    };
+  }
+
+  pub fn write_frame(&mut self, frame: &[u8]) {
+    self.present(frame, None);
+  }
+
+  //only the (start, length) byte ranges of the frame changed since the last frame
+  pub fn write_frame_regions(&mut self, frame: &[u8], ranges: &[(usize, usize)]) {
+    self.present(frame, Some(ranges.to_vec()));
+  }
+
+  fn present(&mut self, frame: &[u8], ranges: Option<Vec<(usize, usize)>>) {
+    if !self.double_buffered {
+      for (start, len) in ranges.unwrap_or(vec![(0, frame.len())]) {
+        self.copy(frame, 0, start, len);
+      }
+      return;
+    }
+    //the back half is two frames old, so it needs what changed last frame too
+    let to_write = match (&ranges, &self.last_ranges) {
+      (Some(ranges), Some(last_ranges)) => ranges.iter().chain(last_ranges.iter()).copied().collect(),
+      _ => vec![(0, frame.len())],
     };
+    let offset = self.back * self.var_screen_info.yres as usize * self.fix_screen_info.line_length as usize;
+    for (start, len) in to_write {
+      self.copy(frame, offset, start, len);
+    }
+    self.last_ranges = ranges;
+    self.var_screen_info.yoffset = self.back as u32 * self.var_screen_info.yres;
+    if self.vsync {
+      self.wait_for_vsync();
+    }
+    if self.pan().is_ok() {
+      self.back = 1 - self.back;
+    } else {
+      //stopped working somehow, so go back to writing straight to the screen
+      self.double_buffered = false;
+      self.var_screen_info.yoffset = 0;
+      let _ = self.pan();
+      self.copy(frame, 0, 0, frame.len());
+    }
   }
 }
 
@@ -144,3 +277,33 @@ impl Drop for Framebuffer {
   }
 }
 
+#[test]
+fn double_buffering() {
+  let path = std::env::temp_dir().join(format!("ming-wm-fb-{}", std::process::id()));
+  let path = path.to_string_lossy();
+  //2x2 screen, 1 byte per pixel, so each half is 4 bytes
+  let frame_len = 4;
+  //not enough virtual screen, so frames go straight to the screen
+  let mut framebuffer = Framebuffer::open_headless(&path, 2, 2, 1).unwrap();
+  assert!(!framebuffer.enable_double_buffering(false));
+  framebuffer.write_frame(&[1, 1, 1, 1]);
+  assert!(std::fs::read(&*path).unwrap() == vec![1, 1, 1, 1]);
+  drop(framebuffer);
+  let _ = std::fs::remove_file(&*path);
+  let mut framebuffer = Framebuffer::open_headless_virtual(&path, 2, 2, 4, 1).unwrap();
+  assert!(framebuffer.enable_double_buffering(false));
+  //written to the half not shown, which is then shown
+  framebuffer.write_frame(&[1, 1, 1, 1]);
+  assert!(std::fs::read(&*path).unwrap()[frame_len..] == [1, 1, 1, 1]);
+  assert!(framebuffer.var_screen_info.yoffset == 2);
+  //the other half never had anything, so all of it is written even if only a region changed
+  framebuffer.write_frame_regions(&[2, 1, 1, 1], &[(0, 1)]);
+  assert!(std::fs::read(&*path).unwrap() == vec![2, 1, 1, 1, 1, 1, 1, 1]);
+  assert!(framebuffer.var_screen_info.yoffset == 0);
+  //this half is two frames old, so it gets what changed last frame as well
+  framebuffer.write_frame_regions(&[2, 1, 1, 3], &[(3, 1)]);
+  assert!(std::fs::read(&*path).unwrap() == vec![2, 1, 1, 1, 2, 1, 1, 3]);
+  assert!(framebuffer.var_screen_info.yoffset == 2);
+  drop(framebuffer);
+  let _ = std::fs::remove_file(&*path);
+}
@@ -13,6 +13,7 @@ pub struct HighlightButton<T> {
   size: Dimensions,
   pub text: String,
   pub highlighted: bool,
+  pub icon: Option<String>, //path to a 16x16 bmp, drawn before the text
   click_return: T,
   toggle_highlight_return: T, //also unhighlight return
 }
@@ -34,18 +35,24 @@ impl<T: Clone> Component<T> for HighlightButton<T> {
 
   fn draw(&self, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
     let font_height = 15;
-    if self.highlighted {
+    let icon_size = 16;
+    let text_left = if self.icon.is_some() { 4 + icon_size + 4 } else { 4 };
+    let mut instructions = if self.highlighted {
       vec![
         //highlight background
         DrawInstructions::Rect(self.top_left, self.size, theme_info.top),
-        DrawInstructions::Text([self.top_left[0] + 4, self.top_left[1] + (self.size[1] - font_height) / 2], vec!["nimbus-roman".to_string()], self.text.clone(), theme_info.top_text, theme_info.top, None, None),
+        DrawInstructions::Text([self.top_left[0] + text_left, self.top_left[1] + (self.size[1] - font_height) / 2], vec!["nimbus-roman".to_string()], self.text.clone(), theme_info.top_text, theme_info.top, None, None),
       ]
     } else {
       vec![
         DrawInstructions::Rect(self.top_left, self.size, theme_info.background),
-        DrawInstructions::Text([self.top_left[0] + 4, self.top_left[1] + (self.size[1] - font_height) / 2], vec!["nimbus-roman".to_string()], self.text.clone(), theme_info.text, theme_info.background, None, None),
+        DrawInstructions::Text([self.top_left[0] + text_left, self.top_left[1] + (self.size[1] - font_height) / 2], vec!["nimbus-roman".to_string()], self.text.clone(), theme_info.text, theme_info.background, None, None),
       ]
+    };
+    if let Some(icon) = &self.icon {
+      instructions.push(DrawInstructions::TransparentBmp([self.top_left[0] + 4, self.top_left[1] + (self.size[1] - icon_size) / 2], icon.to_string(), true));
     }
+    instructions
   }
 
   //properties
@@ -72,6 +79,7 @@ impl<T> HighlightButton<T> {
       click_return,
       toggle_highlight_return,
       highlighted,
+      icon: None,
     }
   }
 }
@@ -13,6 +13,7 @@ pub struct ToggleButton<T> {
   size: Dimensions,
   text: String,
   pub inverted: bool, //whether is it clicked or not
+  pub icon: Option<String>, //path to a 16x16 bmp, drawn before the text
   click_return: T,
   unclick_return: T,
 }
@@ -36,7 +37,9 @@ impl<T: Clone> Component<T> for ToggleButton<T> {
   fn draw(&self, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
     //to make sure the text gets vertically centred
     let font_height = 15;
-    vec![
+    let icon_size = 16;
+    let text_left = if self.icon.is_some() { 4 + icon_size + 4 } else { 4 };
+    let mut instructions = vec![
       //top and left border
       DrawInstructions::Rect(self.top_left, [self.size[0], 2], if self.inverted { theme_info.border_right_bottom } else { theme_info.border_left_top }),
       DrawInstructions::Rect(self.top_left, [2, self.size[1]], if self.inverted { theme_info.border_right_bottom } else { theme_info.border_left_top }),
@@ -46,8 +49,12 @@ impl<T: Clone> Component<T> for ToggleButton<T> {
       //the background if self.draw_bg
       //DrawInstructions::Rect(),
       //the text (for now, hardcoded top left)
-      DrawInstructions::Text([self.top_left[0] + 4, self.top_left[1] + (self.size[1] - font_height) / 2], vec!["nimbus-roman".to_string()], self.text.to_string(), theme_info.text, theme_info.background, None, None),
-    ]
+      DrawInstructions::Text([self.top_left[0] + text_left, self.top_left[1] + (self.size[1] - font_height) / 2], vec!["nimbus-roman".to_string()], self.text.to_string(), theme_info.text, theme_info.background, None, None),
+    ];
+    if let Some(icon) = &self.icon {
+      instructions.push(DrawInstructions::TransparentBmp([self.top_left[0] + 4, self.top_left[1] + (self.size[1] - icon_size) / 2], icon.to_string(), true));
+    }
+    instructions
   }
 
   //properties
@@ -74,6 +81,7 @@ impl<T> ToggleButton<T> {
       click_return,
       unclick_return,
       inverted: false,
+      icon: None,
     }
   }
 }
@@ -38,6 +38,23 @@ pub fn config_dir() -> Option<PathBuf> {
   }
 }
 
+pub fn runtime_dir() -> Option<PathBuf> {
+  //$XDG_RUNTIME_DIR, no fallback since it should only be readable by the user
+  if let Ok(runtime_dir) = env::var("XDG_RUNTIME_DIR") {
+    Some(PathBuf::from(runtime_dir))
+  } else {
+    None
+  }
+}
+
+//ming's clipboard, for programs outside of ming like ming-clip
+pub fn clipboard_file() -> Option<PathBuf> {
+  runtime_dir().map(|mut runtime_dir| {
+    runtime_dir.push("ming-wm-clipboard");
+    runtime_dir
+  })
+}
+
 pub fn exe_dir(add: Option<&str>) -> PathBuf {
   let mut exe_dir = env::current_exe().unwrap();
   exe_dir.pop();
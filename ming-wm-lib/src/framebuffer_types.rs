@@ -1,4 +1,6 @@
 pub type Point = [usize; 2]; //x, y
 pub type Dimensions = [usize; 2]; //width, height
 pub type RGBColor = [u8; 3]; //rgb
+pub type RGBAColor = [u8; 4]; //rgba, alpha of 255 is opaque and 0 is invisible
+pub type Rect = (Point, Dimensions); //top left, dimensions
 
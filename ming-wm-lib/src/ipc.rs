@@ -1,11 +1,12 @@
 use std::io::{ stdin, BufRead };
 use std::panic;
+use std::env;
 
-use crate::window_manager_types::WindowLike;
+use crate::window_manager_types::{ WindowLike, MultiWindowLike, DrawInstructions, WindowLikeType, SizeHints, MenuItem };
 use crate::serialize::Serializable;
 use crate::themes::ThemeInfo;
-use crate::framebuffer_types::Dimensions;
-use crate::messages::WindowMessage;
+use crate::framebuffer_types::{ Dimensions, Rect };
+use crate::messages::{ WindowMessage, WindowMessageResponse };
 use crate::logging::log;
 
 /*
@@ -31,8 +32,23 @@ pub trait WindowLike {
 
 const LOG: bool = false;
 
-/// Listen and process what the window manager writes to our stdin
-pub fn listen(mut window_like: impl WindowLike) {
+/// Set by the window manager when starting a window, if it understands the capabilities line
+pub const CAPABILITIES_ENV: &str = "MING_WM_CAPABILITIES";
+
+/// Methods added after the first version of the protocol. Older windows don't respond to methods they don't know,
+/// so the window manager only sends these to windows that list them in the first line they print: "capabilities <methods>"
+pub const CAPABILITIES: &[&str] = &["size_hints", "icon", "menu", "damage"];
+
+//only if the window manager expects it, since older ones would take it as the response to their first query.
+//removed so processes started by the window (eg: in the terminal) don't print it too
+fn announce_capabilities() {
+  if env::var(CAPABILITIES_ENV).is_ok() {
+    env::remove_var(CAPABILITIES_ENV);
+    println!("capabilities {}", CAPABILITIES.join(" "));
+  }
+}
+
+fn set_panic_hook() {
   panic::set_hook(Box::new(|panic_info| {
     let (filename, line) = panic_info.location().map(|l| (l.file(), l.line())).unwrap_or(("<unknown>", 0));
 
@@ -46,6 +62,61 @@ pub fn listen(mut window_like: impl WindowLike) {
 
     log(&format!("A panic occurred at {}:{}: {}", filename, line, cause));
   }));
+}
+
+//process a line (method and arg) and print the output, if any
+fn respond(window_like: &mut impl WindowLike, line: &str) {
+  let mut parts = line.split(" ");
+  let method = parts.next().unwrap();
+  let arg = &parts.collect::<Vec<&str>>().join(" ");
+  let output = match method {
+    "handle_message" => {
+      //newlines allowed for ClipboardCopy, but represented by the Linear A char
+      window_like.handle_message(WindowMessage::deserialize(arg).unwrap()).serialize().to_string()
+    },
+    "draw" => {
+      //newlines never allowed
+      window_like.draw(&ThemeInfo::deserialize(arg).unwrap()).serialize().replace("\n", "").to_string()
+    },
+    "title" => {
+      window_like.title().to_string()
+    },
+    "resizable" => {
+      window_like.resizable().to_string()
+    },
+    "size_hints" => {
+      window_like.size_hints().serialize().to_string()
+    },
+    "icon" => {
+      //always respond with something, so N for no icon
+      window_like.icon().map_or("N".to_string(), |i| "S".to_string() + &i)
+    },
+    "menu" => {
+      window_like.menu().serialize()
+    },
+    "damage" => {
+      window_like.damage().serialize()
+    },
+    "subtype" => {
+      window_like.subtype().serialize().to_string()
+    },
+    "ideal_dimensions" => {
+      window_like.ideal_dimensions(Dimensions::deserialize(arg).unwrap()).serialize().to_string()
+    },
+    _ => String::new(),
+  };
+  if output != String::new() {
+    if LOG {
+      log(&output);
+    }
+    println!("{}", output);
+  }
+}
+
+/// Listen and process what the window manager writes to our stdin
+pub fn listen(mut window_like: impl WindowLike) {
+  set_panic_hook();
+  announce_capabilities();
 
   let stdin = stdin();
   for line in stdin.lock().lines() {
@@ -53,38 +124,89 @@ pub fn listen(mut window_like: impl WindowLike) {
     if LOG {
       log(&line);
     }
-    let mut parts = line.split(" ");
-    let method = parts.next().unwrap();
-    let arg = &parts.collect::<Vec<&str>>().join(" ");
-    let output = match method {
-      "handle_message" => {
-        //newlines allowed for ClipboardCopy, but represented by the Linear A char
-        window_like.handle_message(WindowMessage::deserialize(arg).unwrap()).serialize().to_string()
-      },
-      "draw" => {
-        //newlines never allowed
-        window_like.draw(&ThemeInfo::deserialize(arg).unwrap()).serialize().replace("\n", "").to_string()
-      },
-      "title" => {
-        window_like.title().to_string()
-      },
-      "resizable" => {
-        window_like.resizable().to_string()
-      },
-      "subtype" => {
-        window_like.subtype().serialize().to_string()
-      },
-      "ideal_dimensions" => {
-        window_like.ideal_dimensions(Dimensions::deserialize(arg).unwrap()).serialize().to_string()
-      },
-      _ => String::new(),
-    };
-    if output != String::new() {
-      if LOG {
-        log(&output);
-      }
-      println!("{}", output);
+    respond(&mut window_like, &line);
+  }
+}
+
+//so one window of a multi window process can be responded to like a normal window
+struct OneOfMulti<'a, M: MultiWindowLike> {
+  multi: &'a mut M,
+  window_id: usize,
+}
+
+impl<M: MultiWindowLike> WindowLike for OneOfMulti<'_, M> {
+  fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
+    self.multi.handle_message(self.window_id, message)
+  }
+
+  fn draw(&self, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    self.multi.draw(self.window_id, theme_info)
+  }
+
+  fn title(&self) -> String {
+    self.multi.title(self.window_id)
+  }
+
+  fn resizable(&self) -> bool {
+    self.multi.resizable(self.window_id)
+  }
+
+  fn size_hints(&self) -> SizeHints {
+    self.multi.size_hints(self.window_id)
+  }
+
+  fn icon(&self) -> Option<String> {
+    self.multi.icon(self.window_id)
+  }
+
+  fn menu(&self) -> Vec<MenuItem> {
+    self.multi.menu(self.window_id)
+  }
+
+  fn damage(&self) -> Option<Vec<Rect>> {
+    self.multi.damage(self.window_id)
+  }
+
+  fn subtype(&self) -> WindowLikeType {
+    self.multi.subtype(self.window_id)
+  }
+
+  fn ideal_dimensions(&self, dimensions: Dimensions) -> Dimensions {
+    self.multi.ideal_dimensions(self.window_id, dimensions)
+  }
+}
+
+//"@<window id> <line>" or just "<line>" for the first window
+fn split_window_id(line: &str) -> (usize, &str) {
+  if let Some((tag, rest)) = line.strip_prefix("@").and_then(|l| l.split_once(" ")) {
+    (tag.parse().unwrap_or(0), rest)
+  } else {
+    (0, line)
+  }
+}
+
+/// Like `listen`, but for processes with more than one window.
+/// Lines for windows other than the first one (window id 0) start with "@<window id> "
+pub fn listen_multi(mut multi: impl MultiWindowLike) {
+  set_panic_hook();
+  announce_capabilities();
+
+  let stdin = stdin();
+  for line in stdin.lock().lines() {
+    let line = line.unwrap().clone();
+    if LOG {
+      log(&line);
     }
+    let (window_id, line) = split_window_id(&line);
+    respond(&mut OneOfMulti { multi: &mut multi, window_id }, line);
   }
 }
 
+#[test]
+fn window_id_tags() {
+  assert!(split_window_id("title") == (0, "title"));
+  assert!(split_window_id("@2 title") == (2, "title"));
+  assert!(split_window_id("@12 handle_message KeyPress/a") == (12, "handle_message KeyPress/a"));
+  //only a tag at the start counts
+  assert!(split_window_id("handle_message @2 x") == (0, "handle_message @2 x"));
+}
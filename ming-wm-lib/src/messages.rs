@@ -23,12 +23,46 @@ impl PartialEq for WindowBox {
 }
 */
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum DialogType {
+  /// Message
+  Message(String),
+  /// Message, for yes or no
+  Confirm(String),
+  /// Message, default text
+  Prompt(String, String),
+  /// Message, starting path
+  FilePicker(String, String),
+  /// Message, starting path
+  DirectoryPicker(String, String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DialogResult {
+  Cancelled,
+  /// Message dismissed, or yes to a confirm
+  Ok,
+  /// Prompt text, or picked path
+  Text(String),
+}
+
 #[derive(Debug, PartialEq)]
 pub enum WindowManagerRequest {
   OpenWindow(String),
   //may not work in \x1E, \x1F or \x1D are in the paste string
   ClipboardCopy(String),
   CloseStartMenu,
+  CloseLauncher,
+  /// Response to `WindowMessage::CloseRequest`, the window stays open
+  DenyClose,
+  /// Response to `WindowMessage::CloseRequest`, ask the user to confirm with the message first
+  ConfirmClose(String),
+  /// Open another window for the same process, with this window id (not 0). See `MultiWindowLike`
+  OpenAdditionalWindow(usize),
+  /// Show a modal dialog for the window. The result is sent back as `WindowMessage::DialogResult`
+  Dialog(DialogType),
+  /// For the dialog only
+  CloseDialog(DialogResult),
   Unlock,
   Lock,
   DoKeyChar(KeyChar),
@@ -104,29 +138,45 @@ pub enum Direction {
 #[derive(PartialEq)]
 pub enum ShortcutType {
   StartMenu,
+  Launcher,
   SwitchWorkspace(u8),
   MoveWindowToWorkspace(u8),
   FocusPrevWindow,
   FocusNextWindow,
   QuitWindow,
+  ForceQuitWindow, //skips asking the window
   MoveWindow(Direction),
   MoveWindowToEdge(Direction),
   ChangeWindowSize(Direction),
+  ChangeWindowSizeLeftTop(Direction), //direction the left or top edge moves in
   CenterWindow,
   FullscreenWindow,
   HalfWidthWindow, //half width, full height
+  QuarterWindow, //cycles through the four corners
+  ThirdWidthWindow, //cycles through left, middle, right
+  TwoThirdsWidthWindow, //cycles through left, right
+  MaximizeWindowVertically,
+  MaximizeWindowHorizontally,
+  ToggleSticky, //show in every workspace
+  ToggleAlwaysOnTop,
+  ToggleScratchpad, //show or hide
+  MoveWindowToScratchpad,
+  OpenMenu, //of the focused window, if it has one
+  WindowHints, //label visible windows with letters to focus them
+  Overview, //of all the workspaces
+  ClipboardHistory, //pick an older clipboard entry to paste
   ClipboardCopy,
   //may not work in \x1E, \x1F or \x1D are in the paste string
   ClipboardPaste(String),
   //
 }
 
-pub type WindowsVec = Vec<(usize, String)>;
+pub type WindowsVec = Vec<(usize, String, String)>;
 
 #[non_exhaustive]
 pub enum InfoType {
   /// Let taskbar know what the current windows in the workspace are
-  WindowsInWorkspace(WindowsVec, usize), //Vec<(id, name)>, focused id
+  WindowsInWorkspace(WindowsVec, usize), //Vec<(id, name, icon path)>, focused id
   //
 }
 
@@ -140,6 +190,12 @@ pub enum WindowMessage {
   Unfocus,
   FocusClick,
   ChangeDimensions(Dimensions),
+  /// Window is about to be closed. Respond with `WindowManagerRequest::DenyClose` or `WindowManagerRequest::ConfirmClose`, anything else means it is closed
+  CloseRequest,
+  /// Result of a dialog the window asked for
+  DialogResult(DialogResult),
+  /// Action of the menu bar item chosen by the user
+  MenuAction(String),
   /// For onscreen keyboard only
   Touch(usize, usize),
   //
@@ -1,9 +1,9 @@
 use std::fmt::Display;
 
 use crate::themes::ThemeInfo;
-use crate::messages::{ WindowMessageResponse, WindowManagerRequest, KeyPress, WindowMessage, Direction, ShortcutType, InfoType };
-use crate::window_manager_types::{ KeyChar, DrawInstructions, WindowLikeType };
-use crate::framebuffer_types::Dimensions;
+use crate::messages::{ WindowMessageResponse, WindowManagerRequest, KeyPress, WindowMessage, Direction, ShortcutType, InfoType, DialogType, DialogResult };
+use crate::window_manager_types::{ KeyChar, DrawInstructions, WindowLikeType, SizeHints, MenuItem };
+use crate::framebuffer_types::{ Point, Dimensions, RGBAColor, Rect };
 use crate::utils::get_rest_of_split;
 
 //serde + ron but worse! yay
@@ -55,6 +55,17 @@ fn get_color(serialized: &str) -> Result<[u8; 3], ()> {
   Ok(color)
 }
 
+//also takes just rgb, which is opaque
+fn get_rgba_color(serialized: &str) -> Result<RGBAColor, ()> {
+  let components: Vec<u8> = serialized.split("\x1F").map(|c| c.parse().map_err(|_| ())).collect::<Result<_, _>>()?;
+  match components[..] {
+    [r, g, b] => Ok([r, g, b, 255]),
+    [r, g, b, a] => Ok([r, g, b, a]),
+    _ => Err(()),
+  }
+}
+
+#[allow(clippy::needless_range_loop)]
 fn get_two_array(serialized: &str) -> Result<[usize; 2], ()> {
   let mut arg = serialized.split("\x1F");
   let mut a = [0; 2];
@@ -70,6 +81,15 @@ fn get_two_array(serialized: &str) -> Result<[usize; 2], ()> {
   Ok(a)
 }
 
+//x and y of each point, all separated by \x1F
+fn get_points(serialized: &str) -> Result<Vec<Point>, ()> {
+  let numbers: Vec<usize> = serialized.split("\x1F").map(|n| n.parse().map_err(|_| ())).collect::<Result<_, _>>()?;
+  if !numbers.len().is_multiple_of(2) {
+    return Err(());
+  }
+  Ok(numbers.chunks(2).map(|p| [p[0], p[1]]).collect())
+}
+
 pub trait Serializable {
   fn serialize(&self) -> String;
   fn deserialize(serialized: &str) -> Result<Self, ()> where Self: Sized;
@@ -148,6 +168,12 @@ impl Serializable for WindowMessageResponse {
           WindowManagerRequest::OpenWindow(name) => format!("OpenWindow/{}", name),
           WindowManagerRequest::ClipboardCopy(copy_string) => format!("ClipboardCopy/{}", copy_string.replace("\n", "𐘂")), //serialised output must be 1 line
           WindowManagerRequest::CloseStartMenu => "CloseStartMenu".to_string(),
+          WindowManagerRequest::CloseLauncher => "CloseLauncher".to_string(),
+          WindowManagerRequest::DenyClose => "DenyClose".to_string(),
+          WindowManagerRequest::ConfirmClose(message) => format!("ConfirmClose/{}", message.replace("\n", "𐘂")),
+          WindowManagerRequest::OpenAdditionalWindow(window_id) => format!("OpenAdditionalWindow/{}", window_id),
+          WindowManagerRequest::Dialog(dialog_type) => format!("Dialog/{}", dialog_type.serialize()),
+          WindowManagerRequest::CloseDialog(result) => format!("CloseDialog/{}", result.serialize()),
           WindowManagerRequest::Unlock => "Unlock".to_string(),
           WindowManagerRequest::Lock => "Lock".to_string(),
           WindowManagerRequest::DoKeyChar(kc) => format!("DoKeyChar/{}", match kc {
@@ -173,6 +199,12 @@ impl Serializable for WindowMessageResponse {
           "OpenWindow" => Some(WindowManagerRequest::OpenWindow(get_rest_of_split(&mut parts, Some("/")))),
           "ClipboardCopy" => Some(WindowManagerRequest::ClipboardCopy(get_rest_of_split(&mut parts, Some("/")))),
           "CloseStartMenu" => Some(WindowManagerRequest::CloseStartMenu),
+          "CloseLauncher" => Some(WindowManagerRequest::CloseLauncher),
+          "DenyClose" => Some(WindowManagerRequest::DenyClose),
+          "ConfirmClose" => Some(WindowManagerRequest::ConfirmClose(get_rest_of_split(&mut parts, Some("/")).replace("𐘂", "\n"))),
+          "OpenAdditionalWindow" => parts.next().and_then(|n| n.parse().ok()).map(WindowManagerRequest::OpenAdditionalWindow),
+          "Dialog" => DialogType::deserialize(&get_rest_of_split(&mut parts, Some("/"))).ok().map(WindowManagerRequest::Dialog),
+          "CloseDialog" => DialogResult::deserialize(&get_rest_of_split(&mut parts, Some("/"))).ok().map(WindowManagerRequest::CloseDialog),
           "Unlock" => Some(WindowManagerRequest::Unlock),
           "Lock" => Some(WindowManagerRequest::Lock),
           "DoKeyChar" => Some(WindowManagerRequest::DoKeyChar(
@@ -210,6 +242,18 @@ fn window_message_response_serialize_deserialize() {
   let resp = WindowMessageResponse::Request(WindowManagerRequest::DoKeyChar(KeyChar::Alt('e')));
   let serialized = resp.serialize();
   assert!(resp == WindowMessageResponse::deserialize(&serialized).unwrap());
+  let resp = WindowMessageResponse::Request(WindowManagerRequest::ConfirmClose("Unsaved changes in a/b.txt\nQuit anyway?".to_string()));
+  let serialized = resp.serialize();
+  assert!(resp == WindowMessageResponse::deserialize(&serialized).unwrap());
+  let resp = WindowMessageResponse::Request(WindowManagerRequest::CloseDialog(DialogResult::Text("/home/a/b.txt".to_string())));
+  let serialized = resp.serialize();
+  assert!(resp == WindowMessageResponse::deserialize(&serialized).unwrap());
+  let resp = WindowMessageResponse::Request(WindowManagerRequest::OpenAdditionalWindow(2));
+  let serialized = resp.serialize();
+  assert!(resp == WindowMessageResponse::deserialize(&serialized).unwrap());
+  let resp = WindowMessageResponse::Request(WindowManagerRequest::Dialog(DialogType::Prompt("Save as:".to_string(), "~/notes/".to_string())));
+  let serialized = resp.serialize();
+  assert!(resp == WindowMessageResponse::deserialize(&serialized).unwrap());
 }
 
 impl Serializable for DrawInstructions {
@@ -220,10 +264,25 @@ impl Serializable for DrawInstructions {
       DrawInstructions::Text(p, vs, s, c1, c2, ou1, ou2) => format!("Text/{}\x1E{}\x1E{}\x1E{}\x1E{}\x1E{}\x1E{}", array_to_string(p), array_to_string(vs), s, array_to_string(c1), array_to_string(c2), option_to_string(ou1), option_to_string(ou2)),
       DrawInstructions::Gradient(p, d, c1, c2, u) => format!("Gradient/{}\x1E{}\x1E{}\x1E{}\x1E{}", array_to_string(p), array_to_string(d), array_to_string(c1), array_to_string(c2), u),
       DrawInstructions::Bmp(p, s, b) => format!("Bmp/{}\x1E{}\x1E{}", array_to_string(p), s, b),
+      DrawInstructions::TransparentBmp(p, s, b) => format!("TransparentBmp/{}\x1E{}\x1E{}", array_to_string(p), s, b),
       DrawInstructions::Circle(p, u, c) => format!("Circle/{}\x1E{}\x1E{}", array_to_string(p), u, array_to_string(c)),
       DrawInstructions::Line(s, e, w, c) => format!("Line/{}\x1E{}\x1E{}\x1E{}", array_to_string(s), array_to_string(e), w, array_to_string(c)),
+      DrawInstructions::AntiAliasedCircle(p, u, c) => format!("AntiAliasedCircle/{}\x1E{}\x1E{}", array_to_string(p), u, array_to_string(c)),
+      DrawInstructions::AntiAliasedLine(s, e, w, c) => format!("AntiAliasedLine/{}\x1E{}\x1E{}\x1E{}", array_to_string(s), array_to_string(e), w, array_to_string(c)),
+      DrawInstructions::RectOutline(p, d, w, c) => format!("RectOutline/{}\x1E{}\x1E{}\x1E{}", array_to_string(p), array_to_string(d), w, array_to_string(c)),
+      DrawInstructions::RoundedRect(p, d, r, c) => format!("RoundedRect/{}\x1E{}\x1E{}\x1E{}", array_to_string(p), array_to_string(d), r, array_to_string(c)),
+      DrawInstructions::Polygon(ps, c) => format!("Polygon/{}\x1E{}", array_to_string(&ps.concat()), array_to_string(c)),
+      DrawInstructions::PolygonOutline(ps, w, c) => format!("PolygonOutline/{}\x1E{}\x1E{}", array_to_string(&ps.concat()), w, array_to_string(c)),
+      DrawInstructions::Arc(p, r, a1, a2, w, c) => format!("Arc/{}\x1E{}\x1E{}\x1E{}\x1E{}\x1E{}", array_to_string(p), r, a1, a2, w, array_to_string(c)),
+      DrawInstructions::Ellipse(p, r, c) => format!("Ellipse/{}\x1E{}\x1E{}", array_to_string(p), array_to_string(r), array_to_string(c)),
+      DrawInstructions::CircleOutline(p, r, w, c) => format!("CircleOutline/{}\x1E{}\x1E{}\x1E{}", array_to_string(p), r, w, array_to_string(c)),
+      DrawInstructions::HorizontalGradient(p, d, c1, c2, u) => format!("HorizontalGradient/{}\x1E{}\x1E{}\x1E{}\x1E{}", array_to_string(p), array_to_string(d), array_to_string(c1), array_to_string(c2), u),
+      DrawInstructions::TranslucentRect(p, d, c) => format!("TranslucentRect/{}\x1E{}\x1E{}", array_to_string(p), array_to_string(d), array_to_string(c)),
+      DrawInstructions::PushClip(p, d) => format!("PushClip/{}\x1E{}", array_to_string(p), array_to_string(d)),
+      DrawInstructions::PopClip => "PopClip".to_string(),
     }
   }
+  #[allow(clippy::manual_ok_err)]
   fn deserialize(serialized: &str) -> Result<Self, ()> {
     //no need to strip newlines cause the impl for Vec<DrawInstructions> does that for us
     let mut parts = serialized.split("/");
@@ -354,7 +413,7 @@ impl Serializable for DrawInstructions {
         }
         Ok(DrawInstructions::Gradient(p, d, c1, c2, u.unwrap()))
       },
-      "Bmp" => {
+      name @ ("Bmp" | "TransparentBmp") => {
         let rest = get_rest_of_split(&mut parts, Some("/"));
         //(p, s, b)
         let mut args = rest.split("\x1E");
@@ -377,9 +436,13 @@ impl Serializable for DrawInstructions {
           return Err(());
         }
         let b = arg == "true";
-        Ok(DrawInstructions::Bmp(p, s.to_string(), b))
+        if name == "Bmp" {
+          Ok(DrawInstructions::Bmp(p, s.to_string(), b))
+        } else {
+          Ok(DrawInstructions::TransparentBmp(p, s.to_string(), b))
+        }
       },
-      "Circle" => {
+      name @ ("Circle" | "AntiAliasedCircle") => {
         let rest = get_rest_of_split(&mut parts, Some("/"));
         //(p, u, c)
         let mut args = rest.split("\x1E");
@@ -401,9 +464,13 @@ impl Serializable for DrawInstructions {
           return Err(());
         }
         let c = get_color(arg.unwrap())?;
-        Ok(DrawInstructions::Circle(p, u.unwrap(), c))
+        if name == "Circle" {
+          Ok(DrawInstructions::Circle(p, u.unwrap(), c))
+        } else {
+          Ok(DrawInstructions::AntiAliasedCircle(p, u.unwrap(), c))
+        }
       },
-      "Line" => {
+      name @ ("Line" | "AntiAliasedLine") => {
         let rest = get_rest_of_split(&mut parts, Some("/"));
         //(s, e, w, c)
         let mut args = rest.split("\x1E");
@@ -430,8 +497,102 @@ impl Serializable for DrawInstructions {
           return Err(());
         }
         let c = get_color(arg.unwrap())?;
-        Ok(DrawInstructions::Line(s, e, w.unwrap(), c))
+        if name == "Line" {
+          Ok(DrawInstructions::Line(s, e, w.unwrap(), c))
+        } else {
+          Ok(DrawInstructions::AntiAliasedLine(s, e, w.unwrap(), c))
+        }
+      },
+      kind @ ("RectOutline" | "RoundedRect") => {
+        let rest = get_rest_of_split(&mut parts, Some("/"));
+        let mut args = rest.split("\x1E");
+        let p = get_two_array(args.next().ok_or(())?)?;
+        let d = get_two_array(args.next().ok_or(())?)?;
+        let u = args.next().ok_or(())?.parse().map_err(|_| ())?;
+        let c = get_color(args.next().ok_or(())?)?;
+        if kind == "RectOutline" {
+          Ok(DrawInstructions::RectOutline(p, d, u, c))
+        } else {
+          Ok(DrawInstructions::RoundedRect(p, d, u, c))
+        }
+      },
+      "Polygon" => {
+        let rest = get_rest_of_split(&mut parts, Some("/"));
+        let mut args = rest.split("\x1E");
+        let ps = get_points(args.next().ok_or(())?)?;
+        let c = get_color(args.next().ok_or(())?)?;
+        Ok(DrawInstructions::Polygon(ps, c))
+      },
+      "PolygonOutline" => {
+        let rest = get_rest_of_split(&mut parts, Some("/"));
+        let mut args = rest.split("\x1E");
+        let ps = get_points(args.next().ok_or(())?)?;
+        let w = args.next().ok_or(())?.parse().map_err(|_| ())?;
+        let c = get_color(args.next().ok_or(())?)?;
+        Ok(DrawInstructions::PolygonOutline(ps, w, c))
+      },
+      "Arc" => {
+        let rest = get_rest_of_split(&mut parts, Some("/"));
+        let mut args = rest.split("\x1E");
+        let p = get_two_array(args.next().ok_or(())?)?;
+        let r = args.next().ok_or(())?.parse().map_err(|_| ())?;
+        let a1 = args.next().ok_or(())?.parse().map_err(|_| ())?;
+        let a2 = args.next().ok_or(())?.parse().map_err(|_| ())?;
+        let w = args.next().ok_or(())?.parse().map_err(|_| ())?;
+        let c = get_color(args.next().ok_or(())?)?;
+        Ok(DrawInstructions::Arc(p, r, a1, a2, w, c))
+      },
+      "Ellipse" => {
+        let rest = get_rest_of_split(&mut parts, Some("/"));
+        let mut args = rest.split("\x1E");
+        let p = get_two_array(args.next().ok_or(())?)?;
+        let r = get_two_array(args.next().ok_or(())?)?;
+        let c = get_color(args.next().ok_or(())?)?;
+        Ok(DrawInstructions::Ellipse(p, r, c))
+      },
+      "CircleOutline" => {
+        let rest = get_rest_of_split(&mut parts, Some("/"));
+        let mut args = rest.split("\x1E");
+        let p = get_two_array(args.next().ok_or(())?)?;
+        let r = args.next().ok_or(())?.parse().map_err(|_| ())?;
+        let w = args.next().ok_or(())?.parse().map_err(|_| ())?;
+        let c = get_color(args.next().ok_or(())?)?;
+        Ok(DrawInstructions::CircleOutline(p, r, w, c))
       },
+      "HorizontalGradient" => {
+        let rest = get_rest_of_split(&mut parts, Some("/"));
+        let mut args = rest.split("\x1E");
+        let p = get_two_array(args.next().ok_or(())?)?;
+        let d = get_two_array(args.next().ok_or(())?)?;
+        let c1 = get_color(args.next().ok_or(())?)?;
+        let c2 = get_color(args.next().ok_or(())?)?;
+        let u = args.next().ok_or(())?.parse().map_err(|_| ())?;
+        Ok(DrawInstructions::HorizontalGradient(p, d, c1, c2, u))
+      },
+      "TranslucentRect" => {
+        let rest = get_rest_of_split(&mut parts, Some("/"));
+        let mut args = rest.split("\x1E");
+        let p = get_two_array(args.next().ok_or(())?)?;
+        let d = get_two_array(args.next().ok_or(())?)?;
+        let c = get_rgba_color(args.next().ok_or(())?)?;
+        Ok(DrawInstructions::TranslucentRect(p, d, c))
+      },
+      "PushClip" => {
+        let rest = get_rest_of_split(&mut parts, Some("/"));
+        let mut args = rest.split("\x1E");
+        let arg = args.next();
+        if arg.is_none() {
+          return Err(());
+        }
+        let p = get_two_array(arg.unwrap())?;
+        let arg = args.next();
+        if arg.is_none() {
+          return Err(());
+        }
+        let d = get_two_array(arg.unwrap())?;
+        Ok(DrawInstructions::PushClip(p, d))
+      },
+      "PopClip" => Ok(DrawInstructions::PopClip),
       _ => Err(()),
     }
   }
@@ -475,7 +636,22 @@ fn draw_instructions_serialize_deserialize() {
     DrawInstructions::Text([123, 999], vec!["nimbus-romono".to_string()], "print!(\"{}\", variable_name);".to_string(), [12, 36, 108], [128, 128, 128], Some(44), Some(200)),
     DrawInstructions::Bmp([55, 98], "mingde".to_string(), true),
     DrawInstructions::Bmp([55, 98], "wooooo".to_string(), false),
+    DrawInstructions::TransparentBmp([55, 98], "ming_bmps/icons/window.bmp".to_string(), true),
     DrawInstructions::Circle([0, 1], 19, [128, 128, 128]),
+    DrawInstructions::AntiAliasedCircle([0, 1], 19, [128, 128, 128]),
+    DrawInstructions::RectOutline([1, 2], [30, 40], 2, [0, 0, 255]),
+    DrawInstructions::RoundedRect([1, 2], [30, 40], 5, [0, 255, 0]),
+    DrawInstructions::Polygon(vec![[0, 0], [10, 0], [5, 8]], [1, 2, 3]),
+    DrawInstructions::PolygonOutline(vec![[0, 0], [10, 0], [5, 8], [0, 4]], 1, [1, 2, 3]),
+    DrawInstructions::Arc([50, 50], 20, 270, 90, 3, [4, 5, 6]),
+    DrawInstructions::Ellipse([50, 50], [20, 10], [7, 8, 9]),
+    DrawInstructions::CircleOutline([50, 50], 20, 1, [10, 11, 12]),
+    DrawInstructions::HorizontalGradient([0, 0], [100, 20], [0, 0, 0], [255, 255, 255], 10),
+    DrawInstructions::TranslucentRect([1, 2], [30, 40], [0, 0, 0, 128]),
+    DrawInstructions::PushClip([10, 20], [30, 40]),
+    DrawInstructions::Line([0, 0], [10, 30], 2, [255, 0, 0]),
+    DrawInstructions::AntiAliasedLine([0, 0], [10, 30], 2, [255, 0, 0]),
+    DrawInstructions::PopClip,
   ];
   let serialized = instructions.serialize();
   assert!(serialized == DrawInstructionsVec::deserialize(&serialized).unwrap().serialize());
@@ -486,7 +662,10 @@ fn draw_instructions_serialize_deserialize() {
   ];
   let serialized = instructions.serialize() + "\n";
   assert!(serialized[..serialized.len() - 1] == DrawInstructionsVec::deserialize(&serialized).unwrap().serialize());
-  let instructions = Vec::new();
+  //3 component colours are opaque
+  assert!(DrawInstructions::deserialize("TranslucentRect/1\x1F2\x1E3\x1F4\x1E5\x1F6\x1F7").unwrap().serialize() == DrawInstructions::TranslucentRect([1, 2], [3, 4], [5, 6, 7, 255]).serialize());
+  assert!(DrawInstructions::deserialize("TranslucentRect/1\x1F2\x1E3\x1F4\x1E5\x1F6").is_err());
+  let instructions: DrawInstructionsVec = Vec::new();
   let serialized = instructions.serialize() + "\n";
   assert!(DrawInstructionsVec::deserialize(&serialized).unwrap().len() == 0);
 }
@@ -501,6 +680,8 @@ impl Serializable for WindowLikeType {
       WindowLikeType::StartMenu => "StartMenu".to_string(),
       WindowLikeType::WorkspaceIndicator => "WorkspaceIndicator".to_string(),
       WindowLikeType::OnscreenKeyboard => "OnscreenKeyboard".to_string(),
+      WindowLikeType::Launcher => "Launcher".to_string(),
+      WindowLikeType::Dialog => "Dialog".to_string(),
     }
   }
   fn deserialize(serialized: &str) -> Result<Self, ()> {
@@ -513,6 +694,8 @@ impl Serializable for WindowLikeType {
       "StartMenu" => Ok(WindowLikeType::StartMenu),
       "WorkspaceIndicator" => Ok(WindowLikeType::WorkspaceIndicator),
       "OnscreenKeyboard" => Ok(WindowLikeType::OnscreenKeyboard),
+      "Launcher" => Ok(WindowLikeType::Launcher),
+      "Dialog" => Ok(WindowLikeType::Dialog),
       _ => Err(()),
     }
   }
@@ -537,6 +720,235 @@ impl Serializable for Dimensions {
   }
 }
 
+impl Serializable for DialogType {
+  fn serialize(&self) -> String {
+    //serialised output must be 1 line
+    match self {
+      DialogType::Message(m) => format!("Message/{}", m),
+      DialogType::Confirm(m) => format!("Confirm/{}", m),
+      DialogType::Prompt(m, t) => format!("Prompt/{}\x1E{}", m, t),
+      DialogType::FilePicker(m, p) => format!("FilePicker/{}\x1E{}", m, p),
+      DialogType::DirectoryPicker(m, p) => format!("DirectoryPicker/{}\x1E{}", m, p),
+    }.replace("\n", "𐘂")
+  }
+  fn deserialize(serialized: &str) -> Result<Self, ()> {
+    let serialized = serialized.strip_suffix("\n").unwrap_or(serialized).replace("𐘂", "\n");
+    let (kind, rest) = serialized.split_once("/").ok_or(())?;
+    match kind {
+      "Message" => Ok(DialogType::Message(rest.to_string())),
+      "Confirm" => Ok(DialogType::Confirm(rest.to_string())),
+      _ => {
+        let (m, s) = rest.split_once("\x1E").ok_or(())?;
+        let (m, s) = (m.to_string(), s.to_string());
+        match kind {
+          "Prompt" => Ok(DialogType::Prompt(m, s)),
+          "FilePicker" => Ok(DialogType::FilePicker(m, s)),
+          "DirectoryPicker" => Ok(DialogType::DirectoryPicker(m, s)),
+          _ => Err(()),
+        }
+      },
+    }
+  }
+}
+
+impl Serializable for DialogResult {
+  fn serialize(&self) -> String {
+    match self {
+      DialogResult::Cancelled => "Cancelled".to_string(),
+      DialogResult::Ok => "Ok".to_string(),
+      DialogResult::Text(t) => format!("Text/{}", t.replace("\n", "𐘂")),
+    }
+  }
+  fn deserialize(serialized: &str) -> Result<Self, ()> {
+    let serialized = serialized.strip_suffix("\n").unwrap_or(serialized);
+    match serialized {
+      "Cancelled" => Ok(DialogResult::Cancelled),
+      "Ok" => Ok(DialogResult::Ok),
+      _ => {
+        //text can have "/"
+        let text = serialized.strip_prefix("Text/").ok_or(())?;
+        Ok(DialogResult::Text(text.replace("𐘂", "\n")))
+      },
+    }
+  }
+}
+
+impl Serializable for SizeHints {
+  fn serialize(&self) -> String {
+    //N or S<width>\x1F<height>, for each of the four
+    [self.min, self.max, self.aspect_ratio, self.increment].iter().map(|o| {
+      if let Some(d) = o {
+        format!("S{}", array_to_string(d))
+      } else {
+        "N".to_string()
+      }
+    }).collect::<Vec<String>>().join("\x1E")
+  }
+  fn deserialize(serialized: &str) -> Result<Self, ()> {
+    let serialized = serialized.strip_suffix("\n").unwrap_or(serialized);
+    let mut options = [None; 4];
+    let mut parts = serialized.split("\x1E");
+    for option in &mut options {
+      let part = parts.next().ok_or(())?;
+      if part != "N" {
+        *option = Some(get_two_array(part.strip_prefix("S").ok_or(())?)?);
+      }
+    }
+    Ok(SizeHints {
+      min: options[0],
+      max: options[1],
+      aspect_ratio: options[2],
+      increment: options[3],
+    })
+  }
+}
+
+#[test]
+fn size_hints_serialize_deserialize() {
+  let size_hints = SizeHints {
+    min: Some([200, 100]),
+    max: None,
+    aspect_ratio: Some([4, 3]),
+    increment: Some([10, 15]),
+  };
+  let serialized = size_hints.serialize();
+  assert!(size_hints == SizeHints::deserialize(&serialized).unwrap());
+  let size_hints: SizeHints = Default::default();
+  let serialized = size_hints.serialize() + "\n";
+  assert!(size_hints == SizeHints::deserialize(&serialized).unwrap());
+  assert!(SizeHints::deserialize("N\x1ES5").is_err());
+}
+
+pub type MenuItemsVec = Vec<MenuItem>;
+
+//flattened (parents before their children), with the depth of each item to get the tree back
+fn flatten_menu_items(items: &[MenuItem], depth: usize, flattened: &mut Vec<String>) {
+  for item in items {
+    flattened.push(format!("{}\x1F{}\x1F{}\x1F{}", depth, item.label, option_to_string(&item.shortcut), item.action));
+    flatten_menu_items(&item.children, depth + 1, flattened);
+  }
+}
+
+fn unflatten_menu_items(flattened: &[(usize, MenuItem)], index: &mut usize, depth: usize) -> Result<MenuItemsVec, ()> {
+  let mut items = Vec::new();
+  while *index < flattened.len() && flattened[*index].0 >= depth {
+    if flattened[*index].0 > depth {
+      //skipped a depth
+      return Err(());
+    }
+    let mut item = flattened[*index].1.clone();
+    *index += 1;
+    item.children = unflatten_menu_items(flattened, index, depth + 1)?;
+    items.push(item);
+  }
+  Ok(items)
+}
+
+impl Serializable for MenuItemsVec {
+  fn serialize(&self) -> String {
+    if self.len() == 0 {
+      return "empty".to_string();
+    }
+    let mut flattened = Vec::new();
+    flatten_menu_items(self, 0, &mut flattened);
+    flattened.join("\x1E")
+  }
+  fn deserialize(serialized: &str) -> Result<Self, ()> {
+    let serialized = serialized.strip_suffix("\n").unwrap_or(serialized);
+    if serialized == "empty" {
+      return Ok(Vec::new());
+    }
+    let mut flattened = Vec::new();
+    for ser_item in serialized.split("\x1E") {
+      let mut parts = ser_item.split("\x1F");
+      let depth = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+      let label = parts.next().ok_or(())?.to_string();
+      let shortcut = parts.next().ok_or(())?;
+      let shortcut = if shortcut == "N" {
+        None
+      } else {
+        Some(shortcut.strip_prefix("S").ok_or(())?.to_string())
+      };
+      let action = parts.next().ok_or(())?.to_string();
+      flattened.push((depth, MenuItem { label, shortcut, action, children: Vec::new() }));
+    }
+    let mut index = 0;
+    let items = unflatten_menu_items(&flattened, &mut index, 0)?;
+    //everything should have been used
+    if index == flattened.len() {
+      Ok(items)
+    } else {
+      Err(())
+    }
+  }
+}
+
+#[test]
+fn menu_items_serialize_deserialize() {
+  use std::vec;
+  let items = vec![
+    MenuItem::submenu("File", vec![
+      MenuItem::new("Open...", "open", None),
+      MenuItem::submenu("Recent", vec![
+        MenuItem::new("a/b.txt", "e a/b.txt", None),
+      ]),
+      MenuItem::new("Write", "w", Some(":w")),
+    ]),
+    MenuItem::new("Help", "help", Some("?")),
+  ];
+  let serialized = items.serialize() + "\n";
+  assert!(items == MenuItemsVec::deserialize(&serialized).unwrap());
+  let items: MenuItemsVec = Vec::new();
+  assert!(items == MenuItemsVec::deserialize(&items.serialize()).unwrap());
+  //starts at depth 1
+  assert!(MenuItemsVec::deserialize("1\x1FFile\x1FN\x1F").is_err());
+}
+
+pub type Damage = Option<Vec<Rect>>;
+
+//N for the whole window, otherwise S then the rects (possibly none)
+impl Serializable for Damage {
+  fn serialize(&self) -> String {
+    if let Some(rects) = self {
+      "S".to_string() + &rects.iter().map(|r| array_to_string(&[r.0[0], r.0[1], r.1[0], r.1[1]])).collect::<Vec<String>>().join("\x1E")
+    } else {
+      "N".to_string()
+    }
+  }
+  fn deserialize(serialized: &str) -> Result<Self, ()> {
+    let serialized = serialized.strip_suffix("\n").unwrap_or(serialized);
+    if serialized == "N" {
+      return Ok(None);
+    }
+    let serialized = serialized.strip_prefix("S").ok_or(())?;
+    let mut rects = Vec::new();
+    if serialized.len() > 0 {
+      for ser_rect in serialized.split("\x1E") {
+        let mut parts = ser_rect.split("\x1F");
+        let mut numbers = [0; 4];
+        for n in &mut numbers {
+          *n = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+        }
+        if parts.next().is_some() {
+          return Err(());
+        }
+        rects.push(([numbers[0], numbers[1]], [numbers[2], numbers[3]]));
+      }
+    }
+    Ok(Some(rects))
+  }
+}
+
+#[test]
+fn damage_serialize_deserialize() {
+  use std::vec;
+  for damage in [None, Some(Vec::new()), Some(vec![([0, 40], [500, 18]), ([3, 4], [5, 6])])] {
+    let serialized = damage.serialize() + "\n";
+    assert!(damage == Damage::deserialize(&serialized).unwrap());
+  }
+  assert!(Damage::deserialize("S1\x1F2\x1F3").is_err());
+}
+
 impl Serializable for WindowMessage {
   fn serialize(&self) -> String {
     match self {
@@ -545,11 +957,17 @@ impl Serializable for WindowMessage {
       WindowMessage::CtrlKeyPress(kp) => format!("CtrlKeyPress/{}", kp.key),
       WindowMessage::Shortcut(st) => format!("Shortcut/{}", match st {
         ShortcutType::StartMenu => "StartMenu".to_string(),
+        ShortcutType::Launcher => "Launcher".to_string(),
         ShortcutType::SwitchWorkspace(u) => format!("SwitchWorkspace/{}", u),
         ShortcutType::MoveWindowToWorkspace(u) => format!("MoveWindowToWorkspace/{}", u),
         ShortcutType::FocusPrevWindow => "FocusPrevWindow".to_string(),
         ShortcutType::FocusNextWindow => "FocusNextWindow".to_string(),
         ShortcutType::QuitWindow => "QuitWindow".to_string(),
+        ShortcutType::ForceQuitWindow => "ForceQuitWindow".to_string(),
+        ShortcutType::OpenMenu => "OpenMenu".to_string(),
+        ShortcutType::WindowHints => "WindowHints".to_string(),
+        ShortcutType::Overview => "Overview".to_string(),
+        ShortcutType::ClipboardHistory => "ClipboardHistory".to_string(),
         ShortcutType::MoveWindow(d) => format!("MoveWindow/{}", match d {
           Direction::Left => "Left",
           Direction::Down => "Down",
@@ -570,7 +988,22 @@ impl Serializable for WindowMessage {
         }),
         ShortcutType::CenterWindow => "CenterWindow".to_string(),
         ShortcutType::FullscreenWindow => "FullscreenWindow".to_string(),
+        ShortcutType::ChangeWindowSizeLeftTop(d) => format!("ChangeWindowSizeLeftTop/{}", match d {
+          Direction::Left => "Left",
+          Direction::Down => "Down",
+          Direction::Up => "Up",
+          Direction::Right => "Right",
+        }),
         ShortcutType::HalfWidthWindow => "HalfWidthWindow".to_string(),
+        ShortcutType::QuarterWindow => "QuarterWindow".to_string(),
+        ShortcutType::ThirdWidthWindow => "ThirdWidthWindow".to_string(),
+        ShortcutType::TwoThirdsWidthWindow => "TwoThirdsWidthWindow".to_string(),
+        ShortcutType::MaximizeWindowVertically => "MaximizeWindowVertically".to_string(),
+        ShortcutType::MaximizeWindowHorizontally => "MaximizeWindowHorizontally".to_string(),
+        ShortcutType::ToggleSticky => "ToggleSticky".to_string(),
+        ShortcutType::ToggleAlwaysOnTop => "ToggleAlwaysOnTop".to_string(),
+        ShortcutType::ToggleScratchpad => "ToggleScratchpad".to_string(),
+        ShortcutType::MoveWindowToScratchpad => "MoveWindowToScratchpad".to_string(),
         ShortcutType::ClipboardCopy => "ClipboardCopy".to_string(),
         ShortcutType::ClipboardPaste(s) => format!("ClipboardPaste/{}", s),
       }),
@@ -578,19 +1011,23 @@ impl Serializable for WindowMessage {
         InfoType::WindowsInWorkspace(wv, u) => {
           let mut wv_string = String::new();
           for w in wv {
-            wv_string += &format!("{}\x1F{}\x1F", w.0, w.1);
+            wv_string += &format!("{}\x1F{}\x1F{}\x1F", w.0, w.1, w.2);
           }
           wv_string = wv_string[..wv_string.len() - 1].to_string();
           format!("WindowsInWorkspace/{}\x1E{}", wv_string, u)
         },
       }),
       WindowMessage::Focus => "Focus".to_string(),
+      WindowMessage::CloseRequest => "CloseRequest".to_string(),
+      WindowMessage::DialogResult(result) => format!("DialogResult/{}", result.serialize()),
+      WindowMessage::MenuAction(action) => format!("MenuAction/{}", action),
       WindowMessage::Unfocus => "Unfocus".to_string(),
       WindowMessage::FocusClick => "FocusClick".to_string(),
       WindowMessage::ChangeDimensions(d) => format!("ChangeDimensions/{}", array_to_string(d)),
       WindowMessage::Touch(u1, u2) => format!("Touch/{}\x1E{}", u1, u2),
     }
   }
+  #[allow(clippy::unnecessary_unwrap)]
   fn deserialize(serialized: &str) -> Result<Self, ()> {
     let serialized = serialized.strip_suffix("\n").unwrap_or(serialized);
     let mut parts = serialized.split("/");
@@ -627,6 +1064,7 @@ impl Serializable for WindowMessage {
         let arg = arg.unwrap();
         let shortcut = match arg {
           "StartMenu" => Some(ShortcutType::StartMenu),
+          "Launcher" => Some(ShortcutType::Launcher),
           "SwitchWorkspace" | "MoveWindowToWorkspace" => {
             let narg = parts.next();
             if narg.is_none() {
@@ -647,7 +1085,12 @@ impl Serializable for WindowMessage {
           "FocusPrevWindow" => Some(ShortcutType::FocusPrevWindow),
           "FocusNextWindow" => Some(ShortcutType::FocusNextWindow),
           "QuitWindow" => Some(ShortcutType::QuitWindow),
-          "MoveWindow" | "MoveWindowToEdge" | "ChangeWindowSize" => {
+          "ForceQuitWindow" => Some(ShortcutType::ForceQuitWindow),
+          "OpenMenu" => Some(ShortcutType::OpenMenu),
+          "WindowHints" => Some(ShortcutType::WindowHints),
+          "Overview" => Some(ShortcutType::Overview),
+          "ClipboardHistory" => Some(ShortcutType::ClipboardHistory),
+          "MoveWindow" | "MoveWindowToEdge" | "ChangeWindowSize" | "ChangeWindowSizeLeftTop" => {
             let darg = parts.next();
             if let Some(darg) = darg {
               let direction = match darg {
@@ -662,6 +1105,8 @@ impl Serializable for WindowMessage {
                   Some(ShortcutType::MoveWindow(direction))
                 } else if arg == "MoveWindowToEdge" {
                   Some(ShortcutType::MoveWindowToEdge(direction))
+                } else if arg == "ChangeWindowSizeLeftTop" {
+                  Some(ShortcutType::ChangeWindowSizeLeftTop(direction))
                 } else {
                   Some(ShortcutType::ChangeWindowSize(direction))
                 }
@@ -675,6 +1120,15 @@ impl Serializable for WindowMessage {
           "CenterWindow" => Some(ShortcutType::CenterWindow),
           "FullscreenWindow" => Some(ShortcutType::FullscreenWindow),
           "HalfWidthWindow" => Some(ShortcutType::HalfWidthWindow),
+          "QuarterWindow" => Some(ShortcutType::QuarterWindow),
+          "ThirdWidthWindow" => Some(ShortcutType::ThirdWidthWindow),
+          "TwoThirdsWidthWindow" => Some(ShortcutType::TwoThirdsWidthWindow),
+          "MaximizeWindowVertically" => Some(ShortcutType::MaximizeWindowVertically),
+          "MaximizeWindowHorizontally" => Some(ShortcutType::MaximizeWindowHorizontally),
+          "ToggleSticky" => Some(ShortcutType::ToggleSticky),
+          "ToggleAlwaysOnTop" => Some(ShortcutType::ToggleAlwaysOnTop),
+          "ToggleScratchpad" => Some(ShortcutType::ToggleScratchpad),
+          "MoveWindowToScratchpad" => Some(ShortcutType::MoveWindowToScratchpad),
           "ClipboardCopy" => Some(ShortcutType::ClipboardCopy),
           "ClipboardPaste" => Some(ShortcutType::ClipboardPaste(get_rest_of_split(&mut parts, Some("/")).replace("𐘂", "\n"))),
           _ => None,
@@ -690,24 +1144,24 @@ impl Serializable for WindowMessage {
         if parts.next().is_none() {
           return Err(());
         }
-        let arg = parts.next();
-        if arg.is_none() {
-          return Err(());
-        }
-        let mut parts2 = arg.unwrap().split("\x1E");
+        //icon paths have slashes
+        let arg = get_rest_of_split(&mut parts, Some("/"));
+        let mut parts2 = arg.split("\x1E");
         let arg2 = parts2.next();
         if arg2.is_none() {
           return Err(());
         }
-        let mut w_tuple: (usize, String) = Default::default();
+        let mut w_tuple: (usize, String, String) = Default::default();
         let mut w_vec = Vec::new();
         for (i, a) in arg2.unwrap().split("\x1F").enumerate() {
-          if i % 2 == 0 {
+          if i % 3 == 0 {
             if let Ok(n) = a.parse() {
               w_tuple.0 = n;
             }
-          } else {
+          } else if i % 3 == 1 {
             w_tuple.1 = a.to_string();
+          } else {
+            w_tuple.2 = a.to_string();
             w_vec.push(w_tuple.clone());
           }
         }
@@ -722,6 +1176,9 @@ impl Serializable for WindowMessage {
         }
       },
       "Focus" => Ok(WindowMessage::Focus),
+      "CloseRequest" => Ok(WindowMessage::CloseRequest),
+      "DialogResult" => Ok(WindowMessage::DialogResult(DialogResult::deserialize(&get_rest_of_split(&mut parts, Some("/")))?)),
+      "MenuAction" => Ok(WindowMessage::MenuAction(get_rest_of_split(&mut parts, Some("/")))),
       "Unfocus" => Ok(WindowMessage::Unfocus),
       "FocusClick" => Ok(WindowMessage::FocusClick),
       "ChangeDimensions" => {
@@ -767,10 +1224,18 @@ fn window_message_serialize_deserialize() {
     WindowMessage::KeyPress(KeyPress { key: '𐘂' }),
     WindowMessage::CtrlKeyPress(KeyPress { key: ';' }),
     WindowMessage::Shortcut(ShortcutType::StartMenu),
+    WindowMessage::Shortcut(ShortcutType::Launcher),
+    WindowMessage::Shortcut(ShortcutType::ToggleAlwaysOnTop),
+    WindowMessage::Shortcut(ShortcutType::ChangeWindowSizeLeftTop(Direction::Up)),
     WindowMessage::Shortcut(ShortcutType::MoveWindowToWorkspace(7)),
     WindowMessage::Shortcut(ShortcutType::ClipboardPaste("105/20 Azumanga".to_string())),
-    WindowMessage::Info(InfoType::WindowsInWorkspace(vec![(1, "Terminal".to_string()), (2, "Minesweeper".to_string()), (12, "Test Test".to_string())], 5)),
+    WindowMessage::Info(InfoType::WindowsInWorkspace(vec![(1, "Terminal".to_string(), "/usr/local/bin/ming_bmps/icons/mingUtils_Terminal.bmp".to_string()), (2, "Minesweeper".to_string(), String::new()), (12, "Test Test".to_string(), String::new())], 5)),
     WindowMessage::Focus,
+    WindowMessage::CloseRequest,
+    WindowMessage::DialogResult(DialogResult::Cancelled),
+    WindowMessage::DialogResult(DialogResult::Text("a/b c".to_string())),
+    WindowMessage::MenuAction("tabe ../x/y".to_string()),
+    WindowMessage::Shortcut(ShortcutType::OpenMenu),
     WindowMessage::Unfocus,
     WindowMessage::FocusClick,
     WindowMessage::ChangeDimensions([999, 250]),
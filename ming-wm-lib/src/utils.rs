@@ -180,6 +180,7 @@ pub fn point_inside(point: Point, top_left: Point, size: Dimensions) -> bool {
   x >= x2 && y >= y2 && x <= x3 && y <= y3
 }
 
+#[allow(clippy::unnecessary_unwrap)]
 pub fn get_rest_of_split(split: &mut dyn Iterator<Item = &str>, sep: Option<&str>) -> String {
   let mut rest = String::new();
   let mut n = split.next();
@@ -1,4 +1,4 @@
-use crate::framebuffer_types::{ Point, Dimensions, RGBColor };
+use crate::framebuffer_types::{ Point, Dimensions, RGBColor, RGBAColor, Rect };
 use crate::themes::ThemeInfo;
 use crate::messages::*;
 
@@ -22,10 +22,38 @@ pub enum DrawInstructions {
   Gradient(Point, Dimensions, RGBColor, RGBColor, usize),
   /// Top left point, path to file, reverse
   Bmp(Point, String, bool),
+  /// Top left point, path to file, reverse. Like `Bmp`, but pixels with 0 alpha are not drawn, eg: icon backgrounds.
+  /// Not the default, since lots of 32 bit BMPs have 0 in the unused alpha byte
+  TransparentBmp(Point, String, bool),
   /// Centre point, radius, colour
   Circle(Point, usize, RGBColor),
   /// Start point, end point, line width, line colour
   Line(Point, Point, usize, RGBColor),
+  /// Centre point, radius, colour. Like `Circle`, but with smooth edges
+  AntiAliasedCircle(Point, usize, RGBColor),
+  /// Start point, end point, line width, line colour. Like `Line`, but with smooth edges. Also properly thick when diagonal, and has round ends
+  AntiAliasedLine(Point, Point, usize, RGBColor),
+  /// Top left point, dimensions, border width, colour. The border is inside the dimensions
+  RectOutline(Point, Dimensions, usize, RGBColor),
+  /// Top left point, dimensions, corner radius, colour
+  RoundedRect(Point, Dimensions, usize, RGBColor),
+  /// Points (at least 3), colour. Filled with the even-odd rule
+  Polygon(Vec<Point>, RGBColor),
+  /// Points, line width, colour. The last point is joined to the first
+  PolygonOutline(Vec<Point>, usize, RGBColor),
+  /// Centre point, radius, start angle, end angle, line width, colour. Angles are in degrees, clockwise from the right. An end angle 360 or more past the start is a full circle
+  Arc(Point, usize, u16, u16, usize, RGBColor),
+  /// Centre point, horizontal radius and vertical radius, colour
+  Ellipse(Point, Dimensions, RGBColor),
+  /// Centre point, radius, line width, colour
+  CircleOutline(Point, usize, usize, RGBColor),
+  /// Top left point, dimensions, start colour, end colour, steps. Left to right, where `Gradient` is top to bottom
+  HorizontalGradient(Point, Dimensions, RGBColor, RGBColor, usize),
+  /// Top left point, dimensions, colour with alpha. Blended with what is already drawn, eg: for highlights and overlays
+  TranslucentRect(Point, Dimensions, RGBAColor),
+  /// Top left point, dimensions. Following instructions only draw inside the rect (and any rect pushed before it) until the matching `PopClip`
+  PushClip(Point, Dimensions),
+  PopClip,
 }
 
 #[derive(Debug, PartialEq)]
@@ -37,6 +65,78 @@ pub enum WindowLikeType {
   StartMenu,
   WorkspaceIndicator,
   OnscreenKeyboard,
+  Launcher,
+  Dialog,
+}
+
+/// Window content dimensions (not including the window top) the window wants. `None` means no preference
+#[derive(Debug, Default, PartialEq)]
+pub struct SizeHints {
+  pub min: Option<Dimensions>,
+  pub max: Option<Dimensions>,
+  /// Width, height
+  pub aspect_ratio: Option<[usize; 2]>,
+  /// Width and height will be a multiple of these, eg: cells of a monospace font
+  pub increment: Option<Dimensions>,
+}
+
+impl SizeHints {
+  /// Fit the dimensions to the hints. `min` is used if there is no min hint, and `max` (the space available) overrides everything
+  pub fn constrain(&self, dimensions: Dimensions, min: Dimensions, max: Dimensions) -> Dimensions {
+    let hint_min = self.min.unwrap_or(min);
+    let hint_max = self.max.unwrap_or(max);
+    let mut constrained = dimensions;
+    for i in 0..2 {
+      constrained[i] = constrained[i].max(hint_min[i]).min(hint_max[i]).min(max[i]);
+    }
+    //only ever shrink from here, so still fits in max
+    if let Some(aspect_ratio) = self.aspect_ratio {
+      if aspect_ratio[0] > 0 && aspect_ratio[1] > 0 {
+        if constrained[0] * aspect_ratio[1] > constrained[1] * aspect_ratio[0] {
+          constrained[0] = constrained[1] * aspect_ratio[0] / aspect_ratio[1];
+        } else {
+          constrained[1] = constrained[0] * aspect_ratio[1] / aspect_ratio[0];
+        }
+      }
+    }
+    if let Some(increment) = self.increment {
+      for i in 0..2 {
+        if increment[i] > 0 && constrained[i] >= increment[i] {
+          constrained[i] -= constrained[i] % increment[i];
+        }
+      }
+    }
+    constrained
+  }
+}
+
+/// An entry in a window's menu bar (opened with Alt+space). Entries with children open a submenu, entries without send `WindowMessage::MenuAction` with their action when chosen
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MenuItem {
+  pub label: String,
+  /// Key(s) that already do the same thing in the window, just shown next to the label. Eg: ":w"
+  pub shortcut: Option<String>,
+  pub action: String,
+  pub children: Vec<MenuItem>,
+}
+
+impl MenuItem {
+  pub fn new(label: &str, action: &str, shortcut: Option<&str>) -> Self {
+    Self {
+      label: label.to_string(),
+      shortcut: shortcut.map(|s| s.to_string()),
+      action: action.to_string(),
+      children: Vec::new(),
+    }
+  }
+
+  pub fn submenu(label: &str, children: Vec<MenuItem>) -> Self {
+    Self {
+      label: label.to_string(),
+      children,
+      ..Default::default()
+    }
+  }
 }
 
 pub trait WindowLike {
@@ -53,8 +153,63 @@ pub trait WindowLike {
     false
   }
 
+  fn size_hints(&self) -> SizeHints {
+    Default::default()
+  }
+
+  /// Path to a 16x16 bmp shown in the window top and taskbar. Fully transparent pixels are not drawn
+  fn icon(&self) -> Option<String> {
+    None
+  }
+
+  /// Top level menus of the menu bar. Empty for no menu bar
+  fn menu(&self) -> Vec<MenuItem> {
+    Vec::new()
+  }
+
+  /// Regions (relative to the window's content, not including the window top or menu bar) that were changed by the last key press, so only they are redrawn to the screen. None if unknown, which means the whole window
+  fn damage(&self) -> Option<Vec<Rect>> {
+    None
+  }
+
   fn subtype(&self) -> WindowLikeType;
 
   fn ideal_dimensions(&self, dimensions: Dimensions) -> Dimensions; //needs &self or its not object safe or some bullcrap
 }
 
+
+/// For processes with more than one window, which share state. Window 0 is the first window, the others are opened with `WindowManagerRequest::OpenAdditionalWindow`. Same as `WindowLike` otherwise
+pub trait MultiWindowLike {
+  fn handle_message(&mut self, window_id: usize, message: WindowMessage) -> WindowMessageResponse;
+
+  fn draw(&self, window_id: usize, theme_info: &ThemeInfo) -> Vec<DrawInstructions>;
+
+  //properties
+  fn title(&self, _window_id: usize) -> String {
+    String::new()
+  }
+
+  fn resizable(&self, _window_id: usize) -> bool {
+    false
+  }
+
+  fn size_hints(&self, _window_id: usize) -> SizeHints {
+    Default::default()
+  }
+
+  fn icon(&self, _window_id: usize) -> Option<String> {
+    None
+  }
+
+  fn menu(&self, _window_id: usize) -> Vec<MenuItem> {
+    Vec::new()
+  }
+
+  fn damage(&self, _window_id: usize) -> Option<Vec<Rect>> {
+    None
+  }
+
+  fn subtype(&self, window_id: usize) -> WindowLikeType;
+
+  fn ideal_dimensions(&self, window_id: usize, dimensions: Dimensions) -> Dimensions;
+}
@@ -0,0 +1,35 @@
+use std::env;
+use std::io::{ stdin, Read };
+use std::fs::{ read_to_string, write };
+use std::process::exit;
+
+use ming_wm_lib::dirs::clipboard_file;
+
+//`ming-clip copy` copies stdin to ming's clipboard, `ming-clip paste` prints ming's clipboard
+//eg, `ls | ming-clip copy` in the terminal, then alt+P in another window
+
+fn main() {
+  let path = if let Some(path) = clipboard_file() {
+    path
+  } else {
+    eprintln!("$XDG_RUNTIME_DIR is not set");
+    exit(1);
+  };
+  match env::args().nth(1).as_deref() {
+    Some("copy") => {
+      let mut content = String::new();
+      if stdin().read_to_string(&mut content).is_err() || write(path, content).is_err() {
+        eprintln!("Failed to copy");
+        exit(1);
+      }
+    },
+    Some("paste") => {
+      //nothing copied yet is just empty
+      print!("{}", read_to_string(path).unwrap_or_default());
+    },
+    _ => {
+      eprintln!("Usage: ming-clip <copy|paste>");
+      exit(1);
+    },
+  }
+}
@@ -44,6 +44,7 @@ struct Draw {
 }
 
 impl WindowLike for Draw {
+  #[allow(clippy::manual_abs_diff)]
   fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
     match message {
       WindowMessage::Init(dimensions) => {
@@ -194,9 +195,9 @@ impl WindowLike for Draw {
     //draw previous actions
     for action in &self.draw_actions {
       instructions.push(match action {
-        DrawAction::Line(p1, p2, lw, c) => DrawInstructions::Line(*p1, p2.unwrap(), *lw, *c),
+        DrawAction::Line(p1, p2, lw, c) => DrawInstructions::AntiAliasedLine(*p1, p2.unwrap(), *lw, *c),
         DrawAction::Rect(p, d, c) => DrawInstructions::Rect(*p, d.unwrap(), *c),
-        DrawAction::Circle(p, r, c) => DrawInstructions::Circle(*p, r.unwrap(), *c),
+        DrawAction::Circle(p, r, c) => DrawInstructions::AntiAliasedCircle(*p, r.unwrap(), *c),
       });
     }
     //draw cursor (crosshair)
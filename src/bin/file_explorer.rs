@@ -123,6 +123,7 @@ impl WindowLike for FileExplorer {
     }
   }
 
+  #[allow(clippy::explicit_counter_loop)]
   fn draw(&self, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
     let mut instructions = Vec::new();
     if self.state == State::List {
@@ -189,6 +190,7 @@ impl FileExplorer {
   }
 
   //should include .. if not /
+  #[allow(clippy::cmp_owned)]
   fn get_current_dir_contents(&self) -> Vec<DirectoryChild> {
     let mut contents = Vec::new();
     if self.current_path != PathBuf::from("/") {
@@ -5,20 +5,23 @@ use std::path::PathBuf;
 use std::collections::HashMap;
 use std::fs::{ read_to_string, write };
 
-use ming_wm_lib::messages::{ WindowMessage, WindowMessageResponse, WindowManagerRequest, ShortcutType };
+use ming_wm_lib::messages::{ WindowMessage, WindowMessageResponse, WindowManagerRequest, ShortcutType, DialogType, DialogResult };
 use ming_wm_lib::themes::ThemeInfo;
-use ming_wm_lib::framebuffer_types::Dimensions;
-use ming_wm_lib::window_manager_types::{ DrawInstructions, WindowLike, WindowLikeType };
-use ming_wm_lib::utils::{ calc_actual_lines, Substring };
+use ming_wm_lib::framebuffer_types::{ Dimensions, Rect };
+use ming_wm_lib::window_manager_types::{ DrawInstructions, WindowLike, MultiWindowLike, WindowLikeType, MenuItem };
+use ming_wm_lib::utils::{ min, calc_actual_lines, Substring };
 use ming_wm_lib::dirs::home;
 use ming_wm_lib::utils::{ get_rest_of_split, path_autocomplete };
-use ming_wm_lib::ipc::listen;
+use ming_wm_lib::ipc::listen_multi;
 
 const MONO_WIDTH: u8 = 10;
 const LINE_HEIGHT: usize = 18;
 const PADDING: usize = 2;
 const BAND_HEIGHT: usize = 19;
 
+//window id of the list of open files, the editor is 0
+const BUFFER_LIST_WINDOW: usize = 1;
+
 const WORD_END: [char; 8] = ['.', ',', ':', '[', ']', '{', '}', ' '];
 
 struct FileInfo {
@@ -92,9 +95,15 @@ struct Malvim {
   current_file_index: usize,
   current: Current,
   autoindent: bool,
+  open_buffer_list: bool,
+  buffer_list_dimensions: Dimensions,
+  buffer_list_selected: usize,
+  opening: Option<String>, //e or t, while the file picker dialog is open
+  damage: Option<Vec<Rect>>, //the current line, if the last message only changed that
 }
 
 impl WindowLike for Malvim {
+  #[allow(clippy::needless_range_loop)]
   fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
     match message {
       WindowMessage::Init(dimensions) => {
@@ -430,6 +439,10 @@ impl WindowLike for Malvim {
             self.prev_command = self.command.take();
             //line above does same as `self.command = None`
             self.mode = Mode::Normal;
+            if self.open_buffer_list {
+              self.open_buffer_list = false;
+              return WindowMessageResponse::Request(WindowManagerRequest::OpenAdditionalWindow(BUFFER_LIST_WINDOW));
+            }
           } else if key_press.key == '\t' { //tab
             let mut parts = command.split(" ").skip(1);
             let parts_len = parts.clone().count();
@@ -515,6 +528,35 @@ impl WindowLike for Malvim {
           _ => WindowMessageResponse::DoNothing,
         }
       },
+      WindowMessage::MenuAction(action) => {
+        if action == "open" || action == "tabopen" {
+          //can only open in a new tab if there are files open already
+          self.opening = Some(if action == "tabopen" && self.files.len() > 0 { "t" } else { "e" }.to_string());
+          let start = if self.files.len() > 0 {
+            PathBuf::from(&self.files[self.current_file_index].path).parent().unwrap().to_string_lossy().to_string() + "/"
+          } else {
+            "~/".to_string()
+          };
+          WindowMessageResponse::Request(WindowManagerRequest::Dialog(DialogType::FilePicker("File to open".to_string(), start)))
+        } else {
+          self.run_command(&action)
+        }
+      },
+      WindowMessage::DialogResult(result) => {
+        if let (Some(command), DialogResult::Text(path)) = (self.opening.take(), result) {
+          self.run_command(&format!("{} {}", command, path))
+        } else {
+          WindowMessageResponse::DoNothing
+        }
+      },
+      WindowMessage::CloseRequest => {
+        let unsaved: Vec<String> = self.files.iter().filter(|f| f.changed).map(|f| f.name.clone()).collect();
+        if unsaved.len() > 0 {
+          WindowMessageResponse::Request(WindowManagerRequest::ConfirmClose(format!("Unsaved changes in {}. Quit anyway?", unsaved.join(", "))))
+        } else {
+          WindowMessageResponse::DoNothing
+        }
+      },
       _ => WindowMessageResponse::DoNothing,
     }
   }
@@ -604,7 +646,32 @@ impl WindowLike for Malvim {
   }
 
   fn title(&self) -> String {
-    "Malvim".to_string()
+    if let Some(current_file) = self.files.get(self.current_file_index) {
+      //like vim, [+] if modified
+      format!("Malvim - {}{}", current_file.name, if current_file.changed { " [+]" } else { "" })
+    } else {
+      "Malvim".to_string()
+    }
+  }
+
+  fn menu(&self) -> Vec<MenuItem> {
+    vec![
+      MenuItem::submenu("File", vec![
+        MenuItem::new("Open...", "open", Some(":e")),
+        MenuItem::new("Open in new tab...", "tabopen", Some(":t")),
+        MenuItem::new("Write", "w", Some(":w")),
+        MenuItem::new("Write and close", "x", Some(":x")),
+        MenuItem::new("Close", "q", Some(":q")),
+      ]),
+      MenuItem::submenu("Buffers", vec![
+        MenuItem::new("Previous", "p", Some(":p")),
+        MenuItem::new("Next", "n", Some(":n")),
+        MenuItem::new("List", "ls", Some(":ls")),
+      ]),
+      MenuItem::submenu("Options", vec![
+        MenuItem::new("Toggle autoindent", "autoindent", Some(":autoindent")),
+      ]),
+    ]
   }
 
   fn subtype(&self) -> WindowLikeType {
@@ -653,6 +720,27 @@ impl Malvim {
     }
   }
 
+  //everything typing in insert mode doesn't change, as long as it stays on the same line
+  fn line_state(&self) -> Option<(usize, bool, usize, usize, usize, usize)> {
+    if self.mode != Mode::Insert || self.files.len() == 0 {
+      return None;
+    }
+    let current_file = &self.files[self.current_file_index];
+    Some((self.current_file_index, current_file.changed, current_file.top_line_pos, current_file.line_pos, current_file.content.len(), self.current.actual_lines.len()))
+  }
+
+  //the display lines of the current line that are on screen
+  fn line_damage(&self) -> Vec<Rect> {
+    let current_file = &self.files[self.current_file_index];
+    let visible = current_file.top_line_pos..(current_file.top_line_pos + self.current.max_lines);
+    let rows: Vec<usize> = self.current.actual_lines.iter().enumerate().filter(|(i, l)| l.1 == current_file.line_pos && visible.contains(i)).map(|(i, _)| i - current_file.top_line_pos).collect();
+    if let (Some(first), Some(last)) = (rows.first(), rows.last()) {
+      vec![([0, BAND_HEIGHT + first * LINE_HEIGHT + PADDING], [self.dimensions[0], (last - first + 1) * LINE_HEIGHT])]
+    } else {
+      Vec::new()
+    }
+  }
+
   fn calc_top_line_pos(&mut self) {
     if self.files.len() == 0 {
       return;
@@ -685,6 +773,24 @@ impl Malvim {
     };
   }
 
+  //same as typing the command and pressing enter
+  fn run_command(&mut self, command: &str) -> WindowMessageResponse {
+    self.bottom_message = None;
+    self.command = Some(command.to_string());
+    let new = self.process_command();
+    self.command = None;
+    self.mode = Mode::Normal;
+    if self.open_buffer_list {
+      self.open_buffer_list = false;
+      return WindowMessageResponse::Request(WindowManagerRequest::OpenAdditionalWindow(BUFFER_LIST_WINDOW));
+    }
+    if new {
+      self.calc_current();
+    }
+    self.calc_top_line_pos();
+    WindowMessageResponse::JustRedraw
+  }
+
   fn process_command(&mut self) -> bool {
     let mut parts = self.command.as_ref().unwrap().split(" ");
     let first = parts.next().unwrap();
@@ -748,6 +854,8 @@ impl Malvim {
       } else {
         self.bottom_message = Some("That is not a file or does not exist".to_string());
       }
+    } else if first == "ls" || first == "buffers" {
+      self.open_buffer_list = true;
     } else if self.files.len() == 0 {
       self.bottom_message = Some("No files are open, so can only do :e(dit)".to_string());
     } else if let Some(s_first) = first.strip_prefix("/") {
@@ -817,6 +925,129 @@ impl Malvim {
   }
 }
 
+impl MultiWindowLike for Malvim {
+  fn handle_message(&mut self, window_id: usize, message: WindowMessage) -> WindowMessageResponse {
+    if window_id == BUFFER_LIST_WINDOW {
+      self.buffer_list_handle_message(message)
+    } else {
+      let before = self.line_state();
+      let response = WindowLike::handle_message(self, message);
+      //so typing only redraws the line being typed on
+      self.damage = if before.is_some() && before == self.line_state() && response == WindowMessageResponse::JustRedraw {
+        Some(self.line_damage())
+      } else {
+        None
+      };
+      response
+    }
+  }
+
+  fn draw(&self, window_id: usize, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    if window_id == BUFFER_LIST_WINDOW {
+      self.buffer_list_draw(theme_info)
+    } else {
+      WindowLike::draw(self, theme_info)
+    }
+  }
+
+  fn title(&self, window_id: usize) -> String {
+    if window_id == BUFFER_LIST_WINDOW {
+      "Malvim Buffers".to_string()
+    } else {
+      WindowLike::title(self)
+    }
+  }
+
+  fn resizable(&self, _window_id: usize) -> bool {
+    true
+  }
+
+  fn menu(&self, window_id: usize) -> Vec<MenuItem> {
+    if window_id == BUFFER_LIST_WINDOW {
+      Vec::new()
+    } else {
+      WindowLike::menu(self)
+    }
+  }
+
+  fn damage(&self, window_id: usize) -> Option<Vec<Rect>> {
+    if window_id == BUFFER_LIST_WINDOW {
+      None
+    } else {
+      self.damage.clone()
+    }
+  }
+
+  fn subtype(&self, _window_id: usize) -> WindowLikeType {
+    WindowLikeType::Window
+  }
+
+  fn ideal_dimensions(&self, window_id: usize, dimensions: Dimensions) -> Dimensions {
+    if window_id == BUFFER_LIST_WINDOW {
+      [250, 200]
+    } else {
+      WindowLike::ideal_dimensions(self, dimensions)
+    }
+  }
+}
+
+//the list of open files, in a separate window
+impl Malvim {
+  fn buffer_list_handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
+    match message {
+      WindowMessage::Init(dimensions) | WindowMessage::ChangeDimensions(dimensions) => {
+        self.buffer_list_dimensions = dimensions;
+        self.buffer_list_selected = self.current_file_index;
+        WindowMessageResponse::JustRedraw
+      },
+      WindowMessage::KeyPress(key_press) => {
+        if self.files.len() == 0 {
+          WindowMessageResponse::DoNothing
+        } else if key_press.key == 'j' || key_press.is_down_arrow() {
+          self.buffer_list_selected = (self.buffer_list_selected + 1) % self.files.len();
+          WindowMessageResponse::JustRedraw
+        } else if key_press.key == 'k' || key_press.is_up_arrow() {
+          self.buffer_list_selected = self.buffer_list_selected.checked_sub(1).unwrap_or(self.files.len() - 1);
+          WindowMessageResponse::JustRedraw
+        } else if key_press.is_enter() {
+          //switch to it in the editor
+          self.current_file_index = self.buffer_list_selected;
+          self.calc_current();
+          self.calc_top_line_pos();
+          WindowMessageResponse::JustRedraw
+        } else {
+          WindowMessageResponse::DoNothing
+        }
+      },
+      _ => WindowMessageResponse::DoNothing,
+    }
+  }
+
+  fn buffer_list_draw(&self, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    let mut instructions = vec![
+      DrawInstructions::Rect([0, 0], self.buffer_list_dimensions, theme_info.background),
+    ];
+    if self.files.len() == 0 {
+      instructions.push(DrawInstructions::Text([4, 4], vec!["nimbus-romono".to_string()], "No files are open".to_string(), theme_info.text, theme_info.background, Some(0), Some(MONO_WIDTH)));
+    }
+    //files can be closed while this is open
+    let selected = min(self.buffer_list_selected, self.files.len().saturating_sub(1));
+    for (i, file_info) in self.files.iter().enumerate() {
+      let (text_color, bg_color) = if i == selected {
+        (theme_info.top_text, theme_info.top)
+      } else {
+        (theme_info.text, theme_info.background)
+      };
+      let line = if i == self.current_file_index { "> " } else { "  " }.to_string() + &file_info.name + if file_info.changed { " [+]" } else { "" };
+      instructions.extend(vec![
+        DrawInstructions::Rect([0, 2 + i * LINE_HEIGHT], [self.buffer_list_dimensions[0], LINE_HEIGHT], bg_color),
+        DrawInstructions::Text([4, 4 + i * LINE_HEIGHT], vec!["nimbus-romono".to_string()], line, text_color, bg_color, Some(0), Some(MONO_WIDTH)),
+      ]);
+    }
+    instructions
+  }
+}
+
 pub fn main() {
-  listen(Malvim::new());
+  listen_multi(Malvim::new());
 }
@@ -134,10 +134,10 @@ impl WindowLike for Reversi {
       ]);
     }
     instructions.extend([
-      DrawInstructions::Circle([5 + square_width * 2, 5 + square_width * 2], 4, [0, 0, 0]),
-      DrawInstructions::Circle([5 + square_width * 6, 5 + square_width * 2], 4, [0, 0, 0]),
-      DrawInstructions::Circle([5 + square_width * 2, 5 + square_width * 6], 4, [0, 0, 0]),
-      DrawInstructions::Circle([5 + square_width * 6, 5 + square_width * 6], 4, [0, 0, 0]),
+      DrawInstructions::AntiAliasedCircle([5 + square_width * 2, 5 + square_width * 2], 4, [0, 0, 0]),
+      DrawInstructions::AntiAliasedCircle([5 + square_width * 6, 5 + square_width * 2], 4, [0, 0, 0]),
+      DrawInstructions::AntiAliasedCircle([5 + square_width * 2, 5 + square_width * 6], 4, [0, 0, 0]),
+      DrawInstructions::AntiAliasedCircle([5 + square_width * 6, 5 + square_width * 6], 4, [0, 0, 0]),
     ]);
     for y in 0..8 {
       for x in 0..8 {
@@ -146,15 +146,10 @@ impl WindowLike for Reversi {
           instructions.push(DrawInstructions::Text([x * square_width + square_width / 2, y * square_width + square_width / 2], vec!["nimbus-roman".to_string()], format!("{}{}", y, x), theme_info.text, REVERSI_GREEN, None, None));
           if valid_moves_contains(&self.valid_moves, &[x, y]).is_some() {
             //yellow border
-            instructions.extend([
-              DrawInstructions::Rect([5 + x * square_width, 5 + y * square_width], [square_width + 2, 2], [255, 255, 0]),
-              DrawInstructions::Rect([5 + x * square_width, 5 + y * square_width], [2, square_width], [255, 255, 0]),
-              DrawInstructions::Rect([5 + (x + 1) * square_width, 5 + y * square_width], [2, square_width], [255, 255, 0]),
-              DrawInstructions::Rect([5 + x * square_width + 2, 5 + (y + 1) * square_width], [square_width + 2, 2], [255, 255, 0]),
-            ]);
+            instructions.push(DrawInstructions::RectOutline([5 + x * square_width, 5 + y * square_width], [square_width + 2, square_width + 2], 2, [255, 255, 0]));
           }
         } else {
-          instructions.push(DrawInstructions::Circle([x * square_width + square_width / 2 + 5, y * square_width + square_width / 2 + 5], square_width / 2 - 3, tile.to_color().unwrap()));
+          instructions.push(DrawInstructions::AntiAliasedCircle([x * square_width + square_width / 2 + 5, y * square_width + square_width / 2 + 5], square_width / 2 - 3, tile.to_color().unwrap()));
         }
       }
     }
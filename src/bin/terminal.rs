@@ -11,8 +11,8 @@ use std::fmt;
 
 use linux::pty::open_pty;
 
-use ming_wm_lib::window_manager_types::{ DrawInstructions, WindowLike, WindowLikeType };
-use ming_wm_lib::messages::{ WindowMessage, WindowMessageResponse, WindowManagerRequest, ShortcutType };
+use ming_wm_lib::window_manager_types::{ DrawInstructions, WindowLike, WindowLikeType, SizeHints, MenuItem };
+use ming_wm_lib::messages::{ WindowMessage, WindowMessageResponse, WindowManagerRequest, ShortcutType, KeyPress };
 use ming_wm_lib::framebuffer_types::Dimensions;
 use ming_wm_lib::themes::ThemeInfo;
 use ming_wm_lib::utils::{ concat_paths, path_autocomplete, Substring };
@@ -268,6 +268,31 @@ impl WindowLike for Terminal {
           _ => WindowMessageResponse::DoNothing,
         }
       },
+      WindowMessage::MenuAction(action) => {
+        match action.as_str() {
+          "clear" => {
+            self.lines = Vec::new();
+            self.calc_actual_lines();
+            self.actual_line_num = 0;
+            WindowMessageResponse::JustRedraw
+          },
+          "copy" => self.handle_message(WindowMessage::Shortcut(ShortcutType::ClipboardCopy)),
+          //same as the ctrl keys
+          "kill" => self.handle_message(WindowMessage::CtrlKeyPress(KeyPress { key: 'c' })),
+          "prev" => self.handle_message(WindowMessage::CtrlKeyPress(KeyPress { key: 'p' })),
+          "next" => self.handle_message(WindowMessage::CtrlKeyPress(KeyPress { key: 'n' })),
+          _ => WindowMessageResponse::DoNothing,
+        }
+      },
+      WindowMessage::CloseRequest => {
+        //the process may have exited without us noticing yet
+        let running = self.running_process.as_mut().is_some_and(|p| matches!(p.try_wait(), Ok(None)));
+        if running {
+          WindowMessageResponse::Request(WindowManagerRequest::ConfirmClose("A command is still running. Quit anyway?".to_string()))
+        } else {
+          WindowMessageResponse::DoNothing
+        }
+      },
       _ => WindowMessageResponse::DoNothing,
     }
   }
@@ -292,9 +317,29 @@ impl WindowLike for Terminal {
   }
 
   fn title(&self) -> String {
+    //show the running command, which is the last one in history
+    if self.mode != Mode::Input {
+      if let Some(command) = self.history.last() {
+        return format!("Terminal - {}", command);
+      }
+    }
     "Terminal".to_string()
   }
 
+  fn menu(&self) -> Vec<MenuItem> {
+    vec![
+      MenuItem::submenu("Terminal", vec![
+        MenuItem::new("Clear", "clear", Some("clear")),
+        MenuItem::new("Copy output", "copy", Some("Alt+C")),
+      ]),
+      MenuItem::submenu("Command", vec![
+        MenuItem::new("Kill", "kill", Some("Ctrl+c")),
+        MenuItem::new("Previous", "prev", Some("Ctrl+p")),
+        MenuItem::new("Next", "next", Some("Ctrl+n")),
+      ]),
+    ]
+  }
+
   fn subtype(&self) -> WindowLikeType {
     WindowLikeType::Window
   }
@@ -306,6 +351,14 @@ impl WindowLike for Terminal {
   fn resizable(&self) -> bool {
     true
   }
+
+  fn size_hints(&self) -> SizeHints {
+    SizeHints {
+      //resize by whole characters and lines
+      increment: Some([MONO_WIDTH as usize, LINE_HEIGHT]),
+      ..Default::default()
+    }
+  }
 }
 
 impl Terminal {
@@ -338,6 +391,7 @@ impl Terminal {
     (self.dimensions[1] - PADDING * 2 - LINE_HEIGHT) / LINE_HEIGHT
   }
 
+  #[allow(clippy::unbuffered_bytes)]
   fn process_command(&mut self) -> Mode {
     if self.current_input.starts_with("clear ") || self.current_input == "clear" {
       self.lines = Vec::new();
@@ -9,7 +9,7 @@ use linux::fb::Framebuffer;
 use linux::raw::RawStdout;
 use linux::keys::{ RawStdin, Key };
 use linux::input::{ Input, EventType };
-use wm::framebuffer::{ FramebufferWriter, FramebufferInfo };
+use wm::framebuffer::{ FramebufferWriter, FramebufferInfo, PixelFormat, Rotation };
 use wm::window_manager::WindowManager;
 
 use ming_wm_lib::window_manager_types::KeyChar;
@@ -49,9 +49,13 @@ pub enum ThreadMessage {
 fn init(framebuffer: Framebuffer, framebuffer_info: FramebufferInfo) {
   let args: Vec<_> = env::args().collect();
 
-  let rotate = args.contains(&"rotate".to_string());
+  //rotate90, rotate180 or rotate270. plain rotate is the same as rotate90
+  let rotation = args.iter().find_map(|arg| match arg.as_str() {
+    "rotate" => Some(Rotation::Deg90),
+    _ => arg.strip_prefix("rotate").and_then(|degrees| degrees.parse().ok()).and_then(Rotation::from_degrees),
+  }).unwrap_or_default();
 
-  let framebuffer_info = if rotate {
+  let framebuffer_info = if rotation.swaps_dimensions() {
     FramebufferInfo {
       byte_len: framebuffer_info.byte_len,
       width: framebuffer_info.height,
@@ -59,6 +63,7 @@ fn init(framebuffer: Framebuffer, framebuffer_info: FramebufferInfo) {
       bytes_per_pixel: framebuffer_info.bytes_per_pixel,
       stride: framebuffer_info.height,
       old_stride: Some(framebuffer_info.stride),
+      format: framebuffer_info.format,
     }
   } else {
     framebuffer_info
@@ -72,7 +77,7 @@ fn init(framebuffer: Framebuffer, framebuffer_info: FramebufferInfo) {
 
   writer.init(framebuffer_info.clone());
 
-  let mut wm: WindowManager = WindowManager::new(writer, framebuffer, dimensions, rotate, grayscale, env!("CARGO_PKG_VERSION").to_string(), PASSWORD_HASH);
+  let mut wm: WindowManager = WindowManager::new(writer, framebuffer, dimensions, rotation, grayscale, env!("CARGO_PKG_VERSION").to_string(), PASSWORD_HASH);
 
   let mut stdout = RawStdout::new(stdout());
   stdout.enter_raw_mode().unwrap();
@@ -83,7 +88,7 @@ fn init(framebuffer: Framebuffer, framebuffer_info: FramebufferInfo) {
 
   stdout.stdout.flush().unwrap();
 
-  wm.draw(None, false);
+  wm.draw(None);
 
   let (tx, rx) = mpsc::channel();
 
@@ -125,11 +130,7 @@ fn init(framebuffer: Framebuffer, framebuffer_info: FramebufferInfo) {
               y = Some(event.value as usize);
             }
             if x.is_some() && y.is_some() {
-              let (x2, y2) = if rotate {
-                (dimensions[0] - y.unwrap(), x.unwrap())
-              } else {
-                (x.unwrap(), y.unwrap())
-              };
+              let [x2, y2] = rotation.from_screen([x.unwrap(), y.unwrap()], dimensions);
               //top right, clear
               //useful sometimes, I think.
               if x2 > dimensions[0] - 100 && y2 < 100 {
@@ -173,15 +174,20 @@ fn init(framebuffer: Framebuffer, framebuffer_info: FramebufferInfo) {
 }
 
 fn main() {
-  let fb = Framebuffer::open("/dev/fb0").unwrap();
+  let mut fb = Framebuffer::open("/dev/fb0").unwrap();
+  //falls back to writing straight to the screen if it can't
+  let vsync = env::args().any(|arg| arg == "vsync");
+  fb.enable_double_buffering(vsync);
   let bytes_per_pixel = (fb.var_screen_info.bits_per_pixel as usize) / 8;
+  //only what is on screen, the virtual screen can be taller (and is, if double buffering)
   let fb_info = FramebufferInfo {
-    byte_len: (fb.var_screen_info.yres_virtual * fb.fix_screen_info.line_length) as usize,
+    byte_len: (fb.var_screen_info.yres * fb.fix_screen_info.line_length) as usize,
     width: fb.var_screen_info.xres_virtual as usize,
-    height: fb.var_screen_info.yres_virtual as usize,
+    height: fb.var_screen_info.yres as usize,
     bytes_per_pixel,
     stride: fb.fix_screen_info.line_length as usize / bytes_per_pixel,
     old_stride: None,
+    format: PixelFormat::from_var_screen_info(&fb.var_screen_info),
   };
 
   init(fb, fb_info);
@@ -0,0 +1,62 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use wm::framebuffer::{ FramebufferWriter, FramebufferInfo, PixelFormat };
+
+//`cargo bench -p wm`. no criterion, just averages over a bunch of runs
+//compares writing the whole frame against only the damaged lines, for typing in a window on a 4k screen
+
+const WIDTH: usize = 3840;
+const HEIGHT: usize = 2160;
+const BYTES_PER_PIXEL: usize = 4;
+const RUNS: u32 = 200;
+
+fn bench(name: &str, mut f: impl FnMut()) {
+  //warm up
+  f();
+  let start = Instant::now();
+  for _ in 0..RUNS {
+    f();
+  }
+  println!("{}: {:?} per run", name, start.elapsed() / RUNS);
+}
+
+fn main() {
+  let mut writer = FramebufferWriter::new(false);
+  writer.init(FramebufferInfo {
+    byte_len: WIDTH * HEIGHT * BYTES_PER_PIXEL,
+    width: WIDTH,
+    height: HEIGHT,
+    bytes_per_pixel: BYTES_PER_PIXEL,
+    stride: WIDTH,
+    old_stride: None,
+    format: PixelFormat::default(),
+  });
+  //stands in for the mmaped framebuffer
+  let mut framebuffer = vec![0u8; WIDTH * HEIGHT * BYTES_PER_PIXEL];
+  //a big window, and one line of text in it (like malvim's)
+  let window_dimensions = [1920, 1080];
+  let window = vec![128u8; window_dimensions[0] * window_dimensions[1] * BYTES_PER_PIXEL];
+  let line = ([0, 400], [window_dimensions[0], 18]);
+  let window_top_left = [300, 200];
+
+  bench("composite whole window", || {
+    writer.draw_buffer(window_top_left, window_dimensions[1], window_dimensions[0] * BYTES_PER_PIXEL, black_box(&window));
+  });
+  bench("composite damaged line", || {
+    writer.draw_buffer_region(window_top_left, window_dimensions[0], line, black_box(&window));
+  });
+  bench("write whole frame", || {
+    framebuffer.copy_from_slice(writer.get_buffer());
+    black_box(&framebuffer);
+  });
+  let damage = [([window_top_left[0] + line.0[0], window_top_left[1] + line.0[1]], line.1)];
+  bench("write damaged lines", || {
+    let ranges = writer.damaged_byte_ranges(black_box(&damage));
+    let frame = writer.get_buffer();
+    for (start, len) in ranges {
+      framebuffer[start..(start + len)].copy_from_slice(&frame[start..(start + len)]);
+    }
+    black_box(&framebuffer);
+  });
+}
@@ -0,0 +1,256 @@
+use std::vec::Vec;
+use std::fs::{ read_to_string, write, create_dir };
+use std::io::Write;
+use std::process::{ Command, Stdio };
+
+use ming_wm_lib::dirs::{ data_dir, clipboard_file };
+
+pub struct ClipboardEntry {
+  pub content: String,
+  pub pinned: bool, //not evicted when the history is full
+  pub sensitive: bool, //never saved, and cleared when locking
+}
+
+//newest first. optionally saved to `$XDG_DATA_HOME/ming-wm/clipboard`
+pub struct ClipboardHistory {
+  pub entries: Vec<ClipboardEntry>,
+  max_entries: usize,
+  persist: bool,
+}
+
+fn history_path() -> Option<String> {
+  data_dir().map(|d| format!("{}/ming-wm/clipboard", d.to_string_lossy()))
+}
+
+impl ClipboardHistory {
+  pub fn new(max_entries: usize, persist: bool) -> Self {
+    let mut history = Self {
+      entries: Vec::new(),
+      max_entries,
+      persist,
+    };
+    if persist {
+      if let Some(path) = history_path() {
+        if let Ok(contents) = read_to_string(path) {
+          history.load(&contents);
+        }
+      }
+    }
+    history
+  }
+
+  //file is lines of "<P if pinned, N otherwise> <content>", newlines in the content are the Linear A char, like ipc
+  fn load(&mut self, contents: &str) {
+    for line in contents.lines() {
+      if let Some((flag, content)) = line.split_once(' ') {
+        self.entries.push(ClipboardEntry {
+          content: content.replace("𐘂", "\n"),
+          pinned: flag == "P",
+          sensitive: false,
+        });
+      }
+    }
+    self.evict();
+  }
+
+  //sensitive entries are left out
+  fn file_contents(&self) -> String {
+    let lines: Vec<String> = self.entries.iter().filter(|e| !e.sensitive).map(|e| {
+      format!("{} {}", if e.pinned { "P" } else { "N" }, e.content.replace("\n", "𐘂"))
+    }).collect();
+    lines.join("\n")
+  }
+
+  pub fn latest(&self) -> Option<&String> {
+    self.entries.first().map(|e| &e.content)
+  }
+
+  pub fn copy(&mut self, content: String) {
+    //copying something already in the history moves it to the front instead
+    if let Some(index) = self.entries.iter().position(|e| e.content == content) {
+      self.promote(index);
+      return;
+    }
+    self.entries.insert(0, ClipboardEntry {
+      content,
+      pinned: false,
+      sensitive: false,
+    });
+    self.evict();
+    self.save();
+  }
+
+  //make it the latest, so it is what gets pasted
+  pub fn promote(&mut self, index: usize) {
+    let entry = self.entries.remove(index);
+    self.entries.insert(0, entry);
+    self.save();
+  }
+
+  pub fn toggle_pinned(&mut self, index: usize) {
+    self.entries[index].pinned = !self.entries[index].pinned;
+    self.save();
+  }
+
+  pub fn toggle_sensitive(&mut self, index: usize) {
+    self.entries[index].sensitive = !self.entries[index].sensitive;
+    self.save();
+  }
+
+  pub fn remove(&mut self, index: usize) {
+    self.entries.remove(index);
+    self.save();
+  }
+
+  pub fn clear_sensitive(&mut self) {
+    self.entries.retain(|e| !e.sensitive);
+  }
+
+  //oldest unpinned entries go first, but never the latest, or what was just copied would be gone.
+  //if everything else is pinned, the history can be over the max
+  fn evict(&mut self) {
+    while self.entries.len() > self.max_entries {
+      if let Some(index) = self.entries.iter().skip(1).rposition(|e| !e.pinned) {
+        self.entries.remove(index + 1);
+      } else {
+        break;
+      }
+    }
+  }
+
+  fn save(&self) {
+    if !self.persist {
+      return;
+    }
+    if let Some(path) = history_path() {
+      let _ = create_dir(format!("{}/ming-wm", data_dir().unwrap().to_string_lossy()));
+      let _ = write(path, self.file_contents());
+    }
+  }
+}
+
+//the clipboard outside of ming. copies always go to the clipboard file (see `clipboard_file`) so ming-clip can read them,
+//and to the copy command if there is one. pastes come from the paste command if there is one, or the clipboard file
+pub struct ExternalClipboard {
+  copy_command: Option<String>,
+  paste_command: Option<String>,
+  last: Option<String>, //last thing written or read, to tell if something outside of ming copied
+}
+
+impl ExternalClipboard {
+  pub fn new(copy_command: Option<String>, paste_command: Option<String>) -> Self {
+    let mut external = Self {
+      copy_command,
+      paste_command,
+      last: None,
+    };
+    //whatever is there from before ming started is not new
+    external.last = external.read();
+    external
+  }
+
+  pub fn write(&mut self, content: &str) {
+    if let Some(path) = clipboard_file() {
+      let _ = write(path, content);
+    }
+    if let Some(copy_command) = &self.copy_command {
+      if let Ok(mut child) = Command::new("sh").arg("-c").arg(copy_command).stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null()).spawn() {
+        if let Some(mut stdin) = child.stdin.take() {
+          let _ = stdin.write_all(content.as_bytes());
+          //stdin is dropped here, so the command gets an eof
+        }
+        let _ = child.wait();
+      }
+    }
+    self.last = Some(content.to_string());
+  }
+
+  fn read(&self) -> Option<String> {
+    if let Some(paste_command) = &self.paste_command {
+      let output = Command::new("sh").arg("-c").arg(paste_command).stdin(Stdio::null()).stderr(Stdio::null()).output().ok()?;
+      if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+      } else {
+        None
+      }
+    } else {
+      read_to_string(clipboard_file()?).ok()
+    }
+  }
+
+  //if it has that content, eg: an entry that was marked sensitive after being copied, so it can't be pasted outside of ming
+  pub fn forget(&mut self, content: &str) {
+    let in_file = clipboard_file().and_then(|path| read_to_string(path).ok()).is_some_and(|c| c == content);
+    if in_file || self.read().is_some_and(|c| c == content) {
+      self.write("");
+    }
+  }
+
+  //only if it changed since ming last wrote or read it
+  pub fn read_new(&mut self) -> Option<String> {
+    let content = self.read().filter(|c| c.len() > 0)?;
+    if self.last.as_ref() == Some(&content) {
+      None
+    } else {
+      self.last = Some(content.clone());
+      Some(content)
+    }
+  }
+}
+
+#[test]
+fn clipboard_eviction_skips_pinned() {
+  let mut history = ClipboardHistory::new(2, false);
+  history.copy("a".to_string());
+  history.toggle_pinned(0);
+  history.copy("b".to_string());
+  history.copy("c".to_string());
+  //"b" is the oldest unpinned, so it goes instead of "a"
+  assert!(history.entries.iter().map(|e| e.content.as_str()).collect::<Vec<_>>() == vec!["c", "a"]);
+  //if everything else is pinned, nothing is evicted, not even the new entry
+  history.toggle_pinned(0);
+  history.copy("d".to_string());
+  assert!(history.entries.iter().map(|e| e.content.as_str()).collect::<Vec<_>>() == vec!["d", "c", "a"]);
+}
+
+#[test]
+fn clipboard_recopy_promotes() {
+  let mut history = ClipboardHistory::new(5, false);
+  history.copy("a".to_string());
+  history.copy("b".to_string());
+  history.toggle_pinned(1);
+  history.copy("a".to_string());
+  assert!(history.entries.len() == 2);
+  assert!(history.latest() == Some(&"a".to_string()));
+  //still pinned
+  assert!(history.entries[0].pinned);
+}
+
+#[test]
+fn clipboard_clear_sensitive() {
+  let mut history = ClipboardHistory::new(5, false);
+  history.copy("password".to_string());
+  history.toggle_sensitive(0);
+  history.copy("not a password".to_string());
+  history.clear_sensitive();
+  assert!(history.entries.len() == 1);
+  assert!(history.latest() == Some(&"not a password".to_string()));
+}
+
+#[test]
+fn clipboard_persist_round_trip() {
+  let mut history = ClipboardHistory::new(5, false);
+  history.copy("two\nlines".to_string());
+  history.copy("secret".to_string());
+  history.toggle_sensitive(0);
+  history.copy("pinned".to_string());
+  history.toggle_pinned(0);
+  let contents = history.file_contents();
+  //newlines are escaped, so each entry is one line
+  assert!(contents == "P pinned\nN two𐘂lines");
+  let mut loaded = ClipboardHistory::new(5, false);
+  loaded.load(&contents);
+  assert!(loaded.entries.len() == 2);
+  assert!(loaded.entries[0].content == "pinned" && loaded.entries[0].pinned);
+  assert!(loaded.entries[1].content == "two\nlines" && !loaded.entries[1].pinned);
+}
@@ -0,0 +1,78 @@
+use std::fs::read_to_string;
+
+use ming_wm_lib::dirs::config_dir;
+
+//`$XDG_CONFIG_DIR/ming-wm/config`, lines of "<key>=<value>"
+//unknown keys and bad values are ignored
+pub struct Config {
+  pub resize_step: usize, //pixels a window grows or shrinks by per resize shortcut
+  pub scale: f32, //pixels per logical pixel, for high dpi screens
+  pub clipboard_size: usize, //max entries in the clipboard history, not counting pinned ones
+  pub clipboard_persist: bool, //save the clipboard history, except sensitive entries
+  pub clipboard_copy_command: Option<String>, //shell command given copied text as stdin
+  pub clipboard_paste_command: Option<String>, //shell command whose stdout is pasted
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      resize_step: 15,
+      scale: 1.0,
+      clipboard_size: 20,
+      clipboard_persist: false,
+      clipboard_copy_command: None,
+      clipboard_paste_command: None,
+    }
+  }
+}
+
+impl Config {
+  pub fn load() -> Self {
+    let mut config: Config = Default::default();
+    if let Some(config_dir) = config_dir() {
+      if let Ok(contents) = read_to_string(format!("{}/ming-wm/config", config_dir.to_string_lossy())) {
+        for line in contents.lines() {
+          if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim();
+            match key.trim() {
+              "resize-step" => {
+                if let Ok(step) = value.parse() {
+                  if step > 0 {
+                    config.resize_step = step;
+                  }
+                }
+              },
+              "scale" => {
+                if let Ok(scale) = value.parse::<f32>() {
+                  if scale > 0.0 {
+                    config.scale = scale;
+                  }
+                }
+              },
+              "clipboard-size" => {
+                if let Ok(size) = value.parse() {
+                  if size > 0 {
+                    config.clipboard_size = size;
+                  }
+                }
+              },
+              "clipboard-persist" => {
+                if let Ok(persist) = value.parse() {
+                  config.clipboard_persist = persist;
+                }
+              },
+              "clipboard-copy-command" if value.len() > 0 => {
+                config.clipboard_copy_command = Some(value.to_string());
+              },
+              "clipboard-paste-command" if value.len() > 0 => {
+                config.clipboard_paste_command = Some(value.to_string());
+              },
+              _ => {},
+            }
+          }
+        }
+      }
+    }
+    config
+  }
+}
@@ -16,6 +16,7 @@ pub struct DesktopBackground {
 }
 
 impl WindowLike for DesktopBackground {
+  #[allow(clippy::collapsible_match)]
   fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
     match message {
       WindowMessage::Init(dimensions) => {
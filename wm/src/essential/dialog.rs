@@ -0,0 +1,207 @@
+use std::vec;
+use std::vec::Vec;
+use std::path::Path;
+
+use ming_wm_lib::window_manager_types::{ DrawInstructions, WindowLike, WindowLikeType };
+use ming_wm_lib::messages::{ WindowMessage, WindowMessageResponse, WindowManagerRequest, ShortcutType, DialogType, DialogResult };
+use ming_wm_lib::framebuffer_types::Dimensions;
+use ming_wm_lib::themes::ThemeInfo;
+use ming_wm_lib::utils::{ min, calc_actual_lines, path_autocomplete, Substring };
+use ming_wm_lib::dirs::home;
+
+const PADDING: usize = 8;
+const LINE_HEIGHT: usize = 18;
+const MONO_WIDTH: u8 = 10;
+
+pub struct Dialog {
+  dimensions: Dimensions,
+  dialog_type: DialogType,
+  input: String, //for prompts and pickers
+  error: Option<String>, //eg, picked path is not a file
+}
+
+impl WindowLike for Dialog {
+  fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
+    match message {
+      WindowMessage::Init(dimensions) => {
+        self.dimensions = dimensions;
+        WindowMessageResponse::JustRedraw
+      },
+      WindowMessage::KeyPress(key_press) => {
+        if key_press.is_escape() {
+          return self.close(DialogResult::Cancelled);
+        }
+        match &self.dialog_type {
+          DialogType::Message(_) => {
+            if key_press.is_enter() {
+              self.close(DialogResult::Ok)
+            } else {
+              WindowMessageResponse::DoNothing
+            }
+          },
+          DialogType::Confirm(_) => {
+            if key_press.is_enter() || key_press.key == 'y' {
+              self.close(DialogResult::Ok)
+            } else if key_press.key == 'n' {
+              self.close(DialogResult::Cancelled)
+            } else {
+              WindowMessageResponse::DoNothing
+            }
+          },
+          _ => {
+            self.error = None;
+            if key_press.is_enter() {
+              self.submit()
+            } else if key_press.is_backspace() {
+              self.input = self.input.remove_last();
+              WindowMessageResponse::JustRedraw
+            } else if key_press.key == '\t' { //tab
+              if self.is_picker() && self.input.len() > 0 {
+                if let Some(add) = path_autocomplete("/", &self.expanded_input()) {
+                  self.input += &add;
+                }
+              }
+              WindowMessageResponse::JustRedraw
+            } else if key_press.is_regular() {
+              self.input.push(key_press.key);
+              WindowMessageResponse::JustRedraw
+            } else {
+              WindowMessageResponse::DoNothing
+            }
+          },
+        }
+      },
+      WindowMessage::Shortcut(ShortcutType::ClipboardPaste(copy_string)) => {
+        if matches!(self.dialog_type, DialogType::Message(_) | DialogType::Confirm(_)) {
+          WindowMessageResponse::DoNothing
+        } else {
+          //single line only
+          self.input += copy_string.lines().next().unwrap_or("");
+          WindowMessageResponse::JustRedraw
+        }
+      },
+      _ => WindowMessageResponse::DoNothing,
+    }
+  }
+
+  fn draw(&self, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    let mut instructions = vec![
+      //left top border
+      DrawInstructions::Rect([0, 0], [self.dimensions[0], 1], theme_info.border_left_top),
+      DrawInstructions::Rect([0, 0], [1, self.dimensions[1]], theme_info.border_left_top),
+      //right bottom border
+      DrawInstructions::Rect([self.dimensions[0] - 1, 0], [1, self.dimensions[1]], theme_info.border_right_bottom),
+      DrawInstructions::Rect([0, self.dimensions[1] - 1], [self.dimensions[0], 1], theme_info.border_right_bottom),
+      //background
+      DrawInstructions::Rect([1, 1], [self.dimensions[0] - 2, self.dimensions[1] - 2], theme_info.background),
+    ];
+    let fonts = vec!["nimbus-romono".to_string()];
+    let lines = Dialog::wrap(self.message(), self.dimensions[0]);
+    let mut y = PADDING;
+    for line in lines {
+      instructions.push(DrawInstructions::Text([PADDING, y], fonts.clone(), line, theme_info.text, theme_info.background, Some(0), Some(MONO_WIDTH)));
+      y += LINE_HEIGHT;
+    }
+    if self.has_input() {
+      //show the end of the input if it is too long, since that is where typing happens
+      let max_chars = (self.dimensions[0] - PADDING * 4) / MONO_WIDTH as usize - 1;
+      let input_len = self.input.chars().count();
+      let shown = self.input.substring(input_len.saturating_sub(max_chars), input_len).to_string() + "|";
+      instructions.extend(vec![
+        DrawInstructions::Rect([PADDING, y], [self.dimensions[0] - PADDING * 2, LINE_HEIGHT], theme_info.alt_background),
+        DrawInstructions::Text([PADDING * 2, y + 2], fonts.clone(), shown, theme_info.alt_text, theme_info.alt_background, Some(0), Some(MONO_WIDTH)),
+      ]);
+    }
+    let hints = if let Some(error) = &self.error {
+      error.to_string()
+    } else {
+      match self.dialog_type {
+        DialogType::Message(_) => "[Enter] Ok",
+        DialogType::Confirm(_) => "[Enter] Yes  [Esc] No",
+        DialogType::Prompt(_, _) => "[Enter] Ok  [Esc] Cancel",
+        _ => "[Tab] Complete  [Enter] Ok  [Esc] Cancel",
+      }.to_string()
+    };
+    instructions.push(DrawInstructions::Text([PADDING, self.dimensions[1] - PADDING - LINE_HEIGHT], fonts, hints, theme_info.top_text, theme_info.top, Some(0), Some(MONO_WIDTH)));
+    instructions
+  }
+
+  //properties
+  fn subtype(&self) -> WindowLikeType {
+    WindowLikeType::Dialog
+  }
+
+  fn ideal_dimensions(&self, dimensions: Dimensions) -> Dimensions {
+    let width = min(if self.has_input() { 500 } else { 400 }, dimensions[0]);
+    //message lines, input line if any, a blank line, then the hints line
+    let lines = Dialog::wrap(self.message(), width).len() + if self.has_input() { 1 } else { 0 } + 2;
+    [width, PADDING * 2 + LINE_HEIGHT * lines]
+  }
+}
+
+impl Dialog {
+  pub fn new(dialog_type: DialogType) -> Self {
+    let input = match &dialog_type {
+      DialogType::Prompt(_, input) | DialogType::FilePicker(_, input) | DialogType::DirectoryPicker(_, input) => input.to_string(),
+      _ => String::new(),
+    };
+    Self {
+      dimensions: [0, 0],
+      dialog_type,
+      input,
+      error: None,
+    }
+  }
+
+  fn message(&self) -> &str {
+    match &self.dialog_type {
+      DialogType::Message(m) | DialogType::Confirm(m) | DialogType::Prompt(m, _) | DialogType::FilePicker(m, _) | DialogType::DirectoryPicker(m, _) => m,
+    }
+  }
+
+  fn has_input(&self) -> bool {
+    !matches!(self.dialog_type, DialogType::Message(_) | DialogType::Confirm(_))
+  }
+
+  fn is_picker(&self) -> bool {
+    matches!(self.dialog_type, DialogType::FilePicker(_, _) | DialogType::DirectoryPicker(_, _))
+  }
+
+  //pickers allow ~ for the home dir
+  fn expanded_input(&self) -> String {
+    if let Some(rest) = self.input.strip_prefix("~") {
+      home().map(|h| h.to_string_lossy().to_string()).unwrap_or_default() + rest
+    } else {
+      self.input.clone()
+    }
+  }
+
+  fn submit(&mut self) -> WindowMessageResponse {
+    match self.dialog_type {
+      DialogType::FilePicker(_, _) | DialogType::DirectoryPicker(_, _) => {
+        let path = self.expanded_input();
+        let is_file = matches!(self.dialog_type, DialogType::FilePicker(_, _));
+        if is_file && !Path::new(&path).is_file() {
+          self.error = Some("Not a file".to_string());
+          WindowMessageResponse::JustRedraw
+        } else if !is_file && !Path::new(&path).is_dir() {
+          self.error = Some("Not a directory".to_string());
+          WindowMessageResponse::JustRedraw
+        } else {
+          self.close(DialogResult::Text(path))
+        }
+      },
+      _ => self.close(DialogResult::Text(self.input.clone())),
+    }
+  }
+
+  fn close(&self, result: DialogResult) -> WindowMessageResponse {
+    WindowMessageResponse::Request(WindowManagerRequest::CloseDialog(result))
+  }
+
+  fn wrap(message: &str, width: usize) -> Vec<String> {
+    let max_chars_per_line = (width - PADDING * 2) / MONO_WIDTH as usize;
+    let lines: Vec<String> = message.lines().map(|l| l.to_string()).collect();
+    calc_actual_lines(lines.iter(), max_chars_per_line, false).into_iter().map(|l| l.2).collect()
+  }
+}
@@ -0,0 +1,288 @@
+use std::vec;
+use std::vec::Vec;
+use std::collections::HashMap;
+use std::fs::{ read_to_string, write, create_dir };
+use std::path::PathBuf;
+
+use ming_wm_lib::window_manager_types::{ DrawInstructions, WindowLike, WindowLikeType };
+use ming_wm_lib::messages::{ WindowMessage, WindowMessageResponse, WindowManagerRequest };
+use ming_wm_lib::framebuffer_types::Dimensions;
+use ming_wm_lib::themes::ThemeInfo;
+use ming_wm_lib::utils::{ min, trunc_words };
+use ming_wm_lib::dirs::data_dir;
+use crate::fs::get_all_executable_windows;
+
+const PADDING: usize = 4;
+const LINE_HEIGHT: usize = 20;
+
+struct LauncherEntry {
+  display: String,
+  category: String,
+  file_name: String,
+}
+
+//0 if no match, higher is better
+//4: exact, 3: prefix, 2: substring, 1: fuzzy (all the query chars appear in order)
+fn match_tier(query: &str, display: &str) -> u8 {
+  let query = query.to_lowercase();
+  let display = display.to_lowercase();
+  if display == query {
+    4
+  } else if display.starts_with(&query) {
+    3
+  } else if display.contains(&query) {
+    2
+  } else {
+    let mut display_chars = display.chars();
+    if query.chars().all(|q| display_chars.any(|d| d == q)) {
+      1
+    } else {
+      0
+    }
+  }
+}
+
+fn counts_path(data_dir: Option<PathBuf>) -> Option<String> {
+  data_dir.map(|d| format!("{}/ming-wm/launcher-counts", d.to_string_lossy()))
+}
+
+//file is lines of "<file name> <launch count>". missing file or bad lines are ignored
+fn read_counts(data_dir: Option<PathBuf>) -> HashMap<String, usize> {
+  let mut counts = HashMap::new();
+  if let Some(path) = counts_path(data_dir) {
+    if let Ok(contents) = read_to_string(path) {
+      for line in contents.lines() {
+        if let Some((name, count)) = line.rsplit_once(' ') {
+          if let Ok(count) = count.parse() {
+            counts.insert(name.to_string(), count);
+          }
+        }
+      }
+    }
+  }
+  counts
+}
+
+#[derive(Default)]
+pub struct Launcher {
+  dimensions: Dimensions,
+  entries: Vec<LauncherEntry>,
+  counts: HashMap<String, usize>, //file name, # of launches
+  query: String,
+  matches: Vec<usize>, //indexes of entries, best first
+  selected: usize,
+}
+
+impl WindowLike for Launcher {
+  fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
+    match message {
+      WindowMessage::Init(dimensions) => {
+        self.dimensions = dimensions;
+        self.entries = vec![
+          LauncherEntry { display: "About".to_string(), category: String::new(), file_name: "About".to_string() },
+          LauncherEntry { display: "Help".to_string(), category: String::new(), file_name: "Help".to_string() },
+        ];
+        for (category, windows) in get_all_executable_windows() {
+          for (display, file_name) in windows {
+            self.entries.push(LauncherEntry {
+              display,
+              category: category["ming".len()..].to_string(),
+              file_name,
+            });
+          }
+        }
+        self.counts = read_counts(data_dir());
+        self.update_matches();
+        WindowMessageResponse::JustRedraw
+      },
+      WindowMessage::KeyPress(key_press) => {
+        if key_press.is_escape() {
+          WindowMessageResponse::Request(WindowManagerRequest::CloseLauncher)
+        } else if key_press.is_enter() {
+          if let Some(entry_index) = self.matches.get(self.selected) {
+            let file_name = self.entries[*entry_index].file_name.clone();
+            self.add_launch(&file_name);
+            WindowMessageResponse::Request(WindowManagerRequest::OpenWindow(file_name))
+          } else {
+            WindowMessageResponse::DoNothing
+          }
+        } else if key_press.is_down_arrow() || key_press.is_up_arrow() {
+          self.move_selection(key_press.is_down_arrow())
+        } else if key_press.is_backspace() {
+          if self.query.len() > 0 {
+            self.query.pop();
+            self.update_matches();
+            WindowMessageResponse::JustRedraw
+          } else {
+            WindowMessageResponse::DoNothing
+          }
+        } else if key_press.is_regular() {
+          self.query.push(key_press.key);
+          self.update_matches();
+          WindowMessageResponse::JustRedraw
+        } else {
+          WindowMessageResponse::DoNothing
+        }
+      },
+      WindowMessage::CtrlKeyPress(key_press) => {
+        //like most terminal things
+        if key_press.key == 'n' || key_press.key == 'p' {
+          self.move_selection(key_press.key == 'n')
+        } else {
+          WindowMessageResponse::DoNothing
+        }
+      },
+      _ => WindowMessageResponse::DoNothing,
+    }
+  }
+
+  fn draw(&self, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    let mut instructions = vec![
+      //left top border
+      DrawInstructions::Rect([0, 0], [self.dimensions[0], 1], theme_info.border_left_top),
+      DrawInstructions::Rect([0, 0], [1, self.dimensions[1]], theme_info.border_left_top),
+      //right bottom border
+      DrawInstructions::Rect([self.dimensions[0] - 1, 0], [1, self.dimensions[1]], theme_info.border_right_bottom),
+      DrawInstructions::Rect([0, self.dimensions[1] - 1], [self.dimensions[0], 1], theme_info.border_right_bottom),
+      //background
+      DrawInstructions::Rect([1, 1], [self.dimensions[0] - 2, self.dimensions[1] - 2], theme_info.background),
+      //query input
+      DrawInstructions::Rect([PADDING, PADDING], [self.dimensions[0] - PADDING * 2, LINE_HEIGHT], theme_info.alt_background),
+      DrawInstructions::Text([PADDING * 2, PADDING + 2], vec!["nimbus-romono".to_string()], "> ".to_string() + &self.query, theme_info.alt_text, theme_info.alt_background, Some(0), Some(10)),
+    ];
+    let max_rows = self.max_rows();
+    //scroll so the selected entry is always visible
+    let first = if self.selected >= max_rows { self.selected - max_rows + 1 } else { 0 };
+    let fonts = vec!["nimbus-roman".to_string()];
+    for (row, entry_index) in self.matches.iter().skip(first).take(max_rows).enumerate() {
+      let entry = &self.entries[*entry_index];
+      let y = PADDING * 2 + LINE_HEIGHT * (row + 1);
+      let (text_color, bg_color) = if first + row == self.selected {
+        (theme_info.top_text, theme_info.top)
+      } else {
+        (theme_info.text, theme_info.background)
+      };
+      let category_width = 70;
+      instructions.extend(vec![
+        DrawInstructions::Rect([PADDING, y], [self.dimensions[0] - PADDING * 2, LINE_HEIGHT], bg_color),
+        DrawInstructions::Text([PADDING * 2, y + 2], fonts.clone(), trunc_words(&fonts, entry.display.clone(), None, self.dimensions[0] - PADDING * 4 - category_width), text_color, bg_color, None, None),
+        DrawInstructions::Text([self.dimensions[0] - PADDING * 2 - category_width, y + 2], fonts.clone(), entry.category.clone(), text_color, bg_color, None, None),
+      ]);
+    }
+    instructions
+  }
+
+  //properties
+  fn subtype(&self) -> WindowLikeType {
+    WindowLikeType::Launcher
+  }
+
+  fn ideal_dimensions(&self, dimensions: Dimensions) -> Dimensions {
+    [min(400, dimensions[0]), PADDING * 3 + LINE_HEIGHT * 11]
+  }
+}
+
+impl Launcher {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  fn max_rows(&self) -> usize {
+    (self.dimensions[1] - PADDING * 3 - LINE_HEIGHT) / LINE_HEIGHT
+  }
+
+  fn move_selection(&mut self, down: bool) -> WindowMessageResponse {
+    if self.matches.len() == 0 {
+      return WindowMessageResponse::DoNothing;
+    }
+    //loops around, like the start menu
+    self.selected = if down {
+      (self.selected + 1) % self.matches.len()
+    } else if self.selected == 0 {
+      self.matches.len() - 1
+    } else {
+      self.selected - 1
+    };
+    WindowMessageResponse::JustRedraw
+  }
+
+  //ranks by how well it matches, then by how many times it was launched
+  fn update_matches(&mut self) {
+    let mut ranked: Vec<(usize, u8, usize)> = self.entries.iter().enumerate().filter_map(|(i, e)| {
+      let tier = match_tier(&self.query, &e.display);
+      if tier > 0 {
+        Some((i, tier, *self.counts.get(&e.file_name).unwrap_or(&0)))
+      } else {
+        None
+      }
+    }).collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then(self.entries[a.0].display.cmp(&self.entries[b.0].display)));
+    self.matches = ranked.into_iter().map(|r| r.0).collect();
+    self.selected = 0;
+  }
+
+  fn add_launch(&mut self, file_name: &str) {
+    *self.counts.entry(file_name.to_string()).or_insert(0) += 1;
+    if let Some(path) = counts_path(data_dir()) {
+      let _ = create_dir(format!("{}/ming-wm", data_dir().unwrap().to_string_lossy()));
+      let contents: Vec<String> = self.counts.iter().map(|(name, count)| format!("{} {}", name, count)).collect();
+      let _ = write(path, contents.join("\n"));
+    }
+  }
+}
+
+#[cfg(test)]
+fn test_launcher(displays: &[&str], counts: &[(&str, usize)], query: &str) -> Launcher {
+  let mut launcher = Launcher::new();
+  launcher.entries = displays.iter().map(|d| LauncherEntry { display: d.to_string(), category: String::new(), file_name: d.to_string() }).collect();
+  launcher.counts = counts.iter().map(|(name, count)| (name.to_string(), *count)).collect();
+  launcher.query = query.to_string();
+  launcher.update_matches();
+  launcher
+}
+
+#[cfg(test)]
+fn match_displays(launcher: &Launcher) -> Vec<&str> {
+  launcher.matches.iter().map(|m| launcher.entries[*m].display.as_str()).collect()
+}
+
+#[test]
+fn launcher_match_order() {
+  //exact > prefix > substring > subsequence, regardless of entry order
+  let launcher = test_launcher(&["Tae Rm", "My Term", "Terminal", "Term", "Audio Player"], &[], "term");
+  assert!(match_displays(&launcher) == vec!["Term", "Terminal", "My Term", "Tae Rm"]);
+  //launch count doesn't beat a better tier
+  let launcher = test_launcher(&["Tae Rm", "My Term", "Terminal", "Term"], &[("Tae Rm", 50), ("My Term", 20), ("Terminal", 10)], "term");
+  assert!(match_displays(&launcher) == vec!["Term", "Terminal", "My Term", "Tae Rm"]);
+}
+
+#[test]
+fn launcher_tie_break_by_count() {
+  let launcher = test_launcher(&["Malvim", "Minesweeper", "Mines"], &[("Minesweeper", 3), ("Malvim", 7)], "m");
+  //all prefix matches, so most launched first, then alphabetical
+  assert!(match_displays(&launcher) == vec!["Malvim", "Minesweeper", "Mines"]);
+}
+
+#[test]
+fn launcher_counts_file() {
+  use std::fs::{ create_dir_all, remove_dir_all };
+  use std::process;
+
+  let data_dir = std::env::temp_dir().join(format!("ming-wm-launcher-test-{}", process::id()));
+  let _ = remove_dir_all(&data_dir);
+  //no data dir at all, or missing file
+  assert!(read_counts(None).is_empty());
+  assert!(read_counts(Some(data_dir.clone())).is_empty());
+  create_dir_all(data_dir.join("ming-wm")).unwrap();
+  let path = counts_path(Some(data_dir.clone())).unwrap();
+  //corrupt lines are skipped, good ones still load
+  write(&path, "Terminal 4\nno-count\nMalvim lots\nMinesweeper -1\n\nFile Explorer 2").unwrap();
+  let counts = read_counts(Some(data_dir.clone()));
+  assert!(counts.len() == 2);
+  assert!(counts.get("Terminal") == Some(&4));
+  assert!(counts.get("File Explorer") == Some(&2));
+  //not utf-8
+  write(&path, [0xff, 0xfe, 0x20, 0x31]).unwrap();
+  assert!(read_counts(Some(data_dir.clone())).is_empty());
+  remove_dir_all(&data_dir).unwrap();
+}
@@ -4,6 +4,8 @@ pub mod lock_screen;
 pub mod workspace_indicator;
 pub mod start_menu;
 pub mod onscreen_keyboard;
+pub mod launcher;
+pub mod dialog;
 
 pub mod about;
 pub mod help;
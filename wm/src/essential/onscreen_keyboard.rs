@@ -51,6 +51,7 @@ enum KeyResponse {
 //because I don't care about ctrl+alt stuff, and won't use it.
 //(and probably not supported by this with a real keyboard anyways)
 #[derive(Default)]
+#[allow(clippy::vec_box)]
 pub struct OnscreenKeyboard {
   dimensions: Dimensions,
   components: Vec<Box<PressButton<KeyResponse>>>,
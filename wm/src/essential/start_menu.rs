@@ -22,6 +22,7 @@ enum StartMenuMessage {
 }
 
 #[derive(Default)]
+#[allow(clippy::vec_box)]
 pub struct StartMenu {
   dimensions: Dimensions,
   executable_windows: ExeWindowInfos,
@@ -162,9 +163,13 @@ impl StartMenu {
               for a in 1..=to_add.len() {
                 let ta = &to_add[a - 1];
                 //the modulo is for multiple pages for windows per category
-                self.components.push(Box::new(HighlightButton::new(
+                let mut button = HighlightButton::new(
                   ta.1.to_string(), [42, (a % max_per_page) * self.y_each], [self.dimensions[0] - 42 - 1, self.y_each], ta.0.to_string(), StartMenuMessage::WindowClick(ta.1.clone()), StartMenuMessage::ChangeAcknowledge, false
-                )));
+                );
+                //same fallback as the window manager, minus asking the window (it isn't running yet)
+                let icon = exe_dir(Some(&format!("ming_bmps/icons/{}.bmp", ta.1)));
+                button.icon = Some(if icon.is_file() { icon } else { exe_dir(Some("ming_bmps/icons/window.bmp")) }.to_string_lossy().to_string());
+                self.components.push(Box::new(button));
               }
             }
             WindowMessageResponse::JustRedraw
@@ -29,6 +29,7 @@ pub struct Taskbar {
 }
 
 impl WindowLike for Taskbar {
+  #[allow(clippy::collapsible_match)]
   fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
     match message {
       WindowMessage::Init(dimensions) => {
@@ -79,10 +80,14 @@ impl WindowLike for Taskbar {
         break;
       }
       let info = &self.windows_in_workspace[wi];
-      let max_text_width = META_WIDTH - PADDING * 2;
+      //room for the icon too
+      let max_text_width = META_WIDTH - PADDING * 3 - 16;
       let name = trunc_words(&["nimbus-roman".to_string()], info.1.clone(), None, max_text_width);
       let mut b = ToggleButton::new(name.to_string() + "-window", [PADDING * 2 + 44 + (META_WIDTH + PADDING) * wi, PADDING], [META_WIDTH, self.dimensions[1] - (PADDING * 2)], name.to_string(), TaskbarMessage::Nothing, TaskbarMessage::Nothing);
       b.inverted = info.0 == self.focused_id;
+      if !info.2.is_empty() {
+        b.icon = Some(info.2.clone());
+      }
       instructions.extend(b.draw(theme_info));
     }
     instructions
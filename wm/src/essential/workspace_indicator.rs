@@ -18,6 +18,7 @@ pub struct WorkspaceIndicator {
 }
 
 impl WindowLike for WorkspaceIndicator {
+  #[allow(clippy::collapsible_match)]
   fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
     match message {
       WindowMessage::Init(dimensions) => {
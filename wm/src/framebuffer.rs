@@ -3,6 +3,7 @@ use std::vec::Vec;
 
 use bmp_rust::bmp::BMP;
 
+use linux::fb::FB_VAR_SCREENINFO;
 use ming_wm_lib::framebuffer_types::*;
 use ming_wm_lib::fonts::{ CachedFontCharGetter, FontCharInfo };
 
@@ -32,6 +33,129 @@ fn color_to_grayscale(color: RGBColor) -> RGBColor {
   [gray; 3]
 }
 
+//where each colour goes in a pixel, as (bit offset, bit length). pixels are little endian, so the offset 0 bit is in
+//the first byte. msb_right (reversed bits) is ignored, since it doesn't seem to be used by anything
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelFormat {
+  pub red: (u32, u32),
+  pub green: (u32, u32),
+  pub blue: (u32, u32),
+  pub transp: (u32, u32), //length 0 if none
+}
+
+//bgra, the most common
+impl Default for PixelFormat {
+  fn default() -> Self {
+    Self {
+      red: (16, 8),
+      green: (8, 8),
+      blue: (0, 8),
+      transp: (24, 8),
+    }
+  }
+}
+
+impl PixelFormat {
+  pub fn from_var_screen_info(var_screen_info: &FB_VAR_SCREENINFO) -> Self {
+    let format = Self {
+      red: (var_screen_info.red.offset, var_screen_info.red.length),
+      green: (var_screen_info.green.offset, var_screen_info.green.length),
+      blue: (var_screen_info.blue.offset, var_screen_info.blue.length),
+      transp: (var_screen_info.transp.offset, var_screen_info.transp.length),
+    };
+    //some drivers leave the bitfields empty, so guess from the bits per pixel
+    if format.red.1 == 0 || format.green.1 == 0 || format.blue.1 == 0 {
+      match var_screen_info.bits_per_pixel {
+        16 => Self { red: (11, 5), green: (5, 6), blue: (0, 5), transp: (0, 0) },
+        24 => Self { transp: (0, 0), ..Default::default() },
+        _ => Default::default(),
+      }
+    } else {
+      format
+    }
+  }
+
+  //the bytes of the pixel, only the first bytes per pixel of them matter
+  pub fn pack(&self, color: RGBColor) -> [u8; 4] {
+    fn channel(value: u8, (offset, length): (u32, u32)) -> u32 {
+      let value = value as u32;
+      //keep the most significant bits if there are less than 8
+      let value = if length < 8 { value >> (8 - length) } else { value << (length - 8) };
+      value << offset
+    }
+    let transp = if self.transp.1 > 0 { ((1u32 << self.transp.1) - 1) << self.transp.0 } else { 0 };
+    (channel(color[0], self.red) | channel(color[1], self.green) | channel(color[2], self.blue) | transp).to_le_bytes()
+  }
+
+  //the colour of a pixel's bytes, for blending with what is already there
+  pub fn unpack(&self, bytes: &[u8]) -> RGBColor {
+    fn channel(value: u32, (offset, length): (u32, u32)) -> u8 {
+      if length == 0 {
+        return 0;
+      }
+      let value = (value >> offset) & ((1u64 << length) - 1) as u32;
+      //stretch less than 8 bits so the max is still 255
+      if length < 8 { (value * 255 / ((1 << length) - 1)) as u8 } else { (value >> (length - 8)) as u8 }
+    }
+    let mut le_bytes = [0; 4];
+    let len = bytes.len().min(4);
+    le_bytes[..len].copy_from_slice(&bytes[..len]);
+    let value = u32::from_le_bytes(le_bytes);
+    [channel(value, self.red), channel(value, self.green), channel(value, self.blue)]
+  }
+}
+
+//for screens turned that many degrees clockwise, so what is drawn is turned the same amount anticlockwise
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Rotation {
+  #[default]
+  Deg0,
+  Deg90,
+  Deg180,
+  Deg270,
+}
+
+impl Rotation {
+  pub fn from_degrees(degrees: usize) -> Option<Self> {
+    match degrees {
+      0 => Some(Rotation::Deg0),
+      90 => Some(Rotation::Deg90),
+      180 => Some(Rotation::Deg180),
+      270 => Some(Rotation::Deg270),
+      _ => None,
+    }
+  }
+
+  //if the screen's width is our height and vice versa
+  pub fn swaps_dimensions(&self) -> bool {
+    *self == Rotation::Deg90 || *self == Rotation::Deg270
+  }
+
+  //where a point in what we draw (with those dimensions) is on the actual screen
+  pub fn to_screen(&self, point: Point, dimensions: Dimensions) -> Point {
+    let [x, y] = point;
+    let [width, height] = dimensions;
+    match self {
+      Rotation::Deg0 => [x, y],
+      Rotation::Deg90 => [y, width - x - 1],
+      Rotation::Deg180 => [width - x - 1, height - y - 1],
+      Rotation::Deg270 => [height - y - 1, x],
+    }
+  }
+
+  //the reverse of `to_screen`, for touches. the screen point may be a little out of bounds, depending on the touchscreen
+  pub fn from_screen(&self, point: Point, dimensions: Dimensions) -> Point {
+    let [x, y] = point;
+    let [width, height] = dimensions;
+    match self {
+      Rotation::Deg0 => [x, y],
+      Rotation::Deg90 => [width.saturating_sub(y + 1), x],
+      Rotation::Deg180 => [width.saturating_sub(x + 1), height.saturating_sub(y + 1)],
+      Rotation::Deg270 => [y, height.saturating_sub(x + 1)],
+    }
+  }
+}
+
 #[derive(Clone, Default)]
 pub struct FramebufferInfo {
   pub byte_len: usize,
@@ -39,16 +163,20 @@ pub struct FramebufferInfo {
   pub height: usize,
   pub bytes_per_pixel: usize,
   pub stride: usize,
-  pub old_stride: Option<usize>, //used/set only when rotate is true
+  pub old_stride: Option<usize>, //the screen's stride, used/set only when rotated 90 or 270 degrees
+  pub format: PixelFormat,
 }
 
-//currently doesn't check if writing onto next line accidentally
+//drawing is clipped to the buffer, and to the top of the clip stack if anything is pushed.
+//positions and sizes given to the draw functions are in logical units, which are `scale` pixels each, so the same
+//drawing is bigger on high dpi screens. buffers (and the info) are in actual pixels
 pub struct FramebufferWriter {
   info: FramebufferInfo,
+  scale: f32,
+  clips: Vec<Rect>, //in pixels, each already inside the one before it
   fc_getter: CachedFontCharGetter,
   buffer: Vec<u8>,
-  saved_buffer: Option<Vec<u8>>,
-  rotate_buffer: Option<Vec<u8>>,
+  rotate_buffer: Vec<u8>, //reused every frame, empty if never rotated
   grayscale: bool,
 }
 
@@ -56,10 +184,11 @@ impl FramebufferWriter {
   pub fn new(grayscale: bool) -> Self {
     Self {
       info: Default::default(),
+      scale: 1.0,
+      clips: Vec::new(),
       fc_getter: CachedFontCharGetter::new(128), //an arbitrary high-ish number for max cache size
       buffer: Vec::new(),
-      saved_buffer: None,
-      rotate_buffer: None,
+      rotate_buffer: Vec::new(),
       grayscale,
     }
   }
@@ -72,40 +201,129 @@ impl FramebufferWriter {
   pub fn get_info(&self) -> FramebufferInfo {
     self.info.clone()
   }
+
+  //does not need to be a whole number
+  pub fn set_scale(&mut self, scale: f32) {
+    self.scale = scale;
+  }
+
+  //logical units to pixels
+  pub fn scaled(&self, value: usize) -> usize {
+    (value as f32 * self.scale).round() as usize
+  }
+
+  fn scaled_point(&self, point: Point) -> Point {
+    [self.scaled(point[0]), self.scaled(point[1])]
+  }
+
+  //scaling the bottom right instead of the dimensions means rects next to each other stay next to each other,
+  //without gaps or overlaps, when the scale isn't a whole number
+  fn scaled_rect(&self, top_left: Point, dimensions: Dimensions) -> Rect {
+    let start = self.scaled_point(top_left);
+    let end = self.scaled_point([top_left[0] + dimensions[0], top_left[1] + dimensions[1]]);
+    (start, [end[0] - start[0], end[1] - start[1]])
+  }
   
   pub fn get_buffer(&mut self) -> &[u8] {
     &self.buffer
   }
 
-  pub fn get_transposed_buffer(&mut self) -> &[u8] {
-    let mut output_array = vec![255; self.info.byte_len];
-    let row_bytes_len = self.info.stride * self.info.bytes_per_pixel;
-    let row_bytes_len_transposed = self.info.old_stride.unwrap_or(self.info.height) * self.info.bytes_per_pixel;
+  //the buffer as it should be on the screen
+  pub fn get_rotated_buffer(&mut self, rotation: Rotation) -> &[u8] {
+    if rotation == Rotation::Deg0 {
+      return &self.buffer;
+    }
+    let bytes_per_pixel = self.info.bytes_per_pixel;
+    let row_bytes_len = self.info.stride * bytes_per_pixel;
+    let default_stride = if rotation.swaps_dimensions() { self.info.height } else { self.info.stride };
+    let row_bytes_len_rotated = self.info.old_stride.unwrap_or(default_stride) * bytes_per_pixel;
+    if self.rotate_buffer.len() != self.info.byte_len {
+      self.rotate_buffer = vec![0; self.info.byte_len];
+    }
+    let dimensions = [self.info.width, self.info.height];
     for y in 0..self.info.height {
       for x in 0..self.info.width {
-        for i in 0..self.info.bytes_per_pixel {
-          output_array[(self.info.width - x - 1) * row_bytes_len_transposed + y * self.info.bytes_per_pixel + i] = self.buffer[y * row_bytes_len + x * self.info.bytes_per_pixel + i];
-        }
+        let [rx, ry] = rotation.to_screen([x, y], dimensions);
+        let from = y * row_bytes_len + x * bytes_per_pixel;
+        let to = ry * row_bytes_len_rotated + rx * bytes_per_pixel;
+        self.rotate_buffer[to..(to + bytes_per_pixel)].copy_from_slice(&self.buffer[from..(from + bytes_per_pixel)]);
       }
     }
-    self.rotate_buffer = Some(output_array);
-    self.rotate_buffer.as_ref().unwrap()
+    &self.rotate_buffer
+  }
+
+  //bytes of a pixel of that colour, in our pixel format
+  fn pixel_bytes(&self, color: RGBColor) -> [u8; 4] {
+    let color = if self.grayscale { color_to_grayscale(color) } else { color };
+    self.info.format.pack(color)
+  }
+
+  //the rect drawing is confined to, in pixels. the whole buffer if nothing is pushed
+  fn clip(&self) -> Rect {
+    self.clips.last().copied().unwrap_or(([0, 0], [self.info.width, self.info.height]))
+  }
+
+  //the part of the rect (in pixels) inside the clip rect, if any
+  fn clip_rect(&self, top_left: Point, dimensions: Dimensions) -> Option<Rect> {
+    let clip = self.clip();
+    let start = [top_left[0].max(clip.0[0]), top_left[1].max(clip.0[1])];
+    let end = [(top_left[0] + dimensions[0]).min(clip.0[0] + clip.1[0]), (top_left[1] + dimensions[1]).min(clip.0[1] + clip.1[1])];
+    if start[0] < end[0] && start[1] < end[1] {
+      Some((start, [end[0] - start[0], end[1] - start[1]]))
+    } else {
+      None
+    }
+  }
+
+  //drawing is confined to where this and the current clip rect overlap, until it is popped
+  pub fn push_clip(&mut self, top_left: Point, dimensions: Dimensions) {
+    let (top_left, dimensions) = self.scaled_rect(top_left, dimensions);
+    let clip = self.clip_rect(top_left, dimensions).unwrap_or(([0, 0], [0, 0]));
+    self.clips.push(clip);
   }
 
-  pub fn save_buffer(&mut self) {
-    self.saved_buffer = Some(self.buffer.clone());
+  pub fn pop_clip(&mut self) {
+    self.clips.pop();
   }
 
-  pub fn write_saved_buffer_to_raw(&mut self) {
-    self.buffer[..]
-      .copy_from_slice(&self.saved_buffer.as_ref().unwrap()[..]);
+  //for when pushes weren't all popped
+  pub fn clear_clips(&mut self) {
+    self.clips.clear();
+  }
+
+  //point is in pixels, and may be off the buffer (even negative)
+  fn _draw_pixel_at(&mut self, point: [isize; 2], color: RGBColor) {
+    let clip = self.clip();
+    let inside_x = point[0] >= clip.0[0] as isize && point[0] < (clip.0[0] + clip.1[0]) as isize;
+    let inside_y = point[1] >= clip.0[1] as isize && point[1] < (clip.0[1] + clip.1[1]) as isize;
+    if inside_x && inside_y {
+      let start_pos = (point[1] as usize * self.info.stride + point[0] as usize) * self.info.bytes_per_pixel;
+      self._draw_pixel(start_pos, color);
+    }
+  }
+
+  //alpha blended with what is already there. like `_draw_pixel_at`, point is in pixels and may be off the buffer
+  fn _blend_pixel_at(&mut self, point: [isize; 2], color: RGBColor, alpha: u8) {
+    let clip = self.clip();
+    let inside_x = point[0] >= clip.0[0] as isize && point[0] < (clip.0[0] + clip.1[0]) as isize;
+    let inside_y = point[1] >= clip.0[1] as isize && point[1] < (clip.0[1] + clip.1[1]) as isize;
+    if alpha > 0 && inside_x && inside_y {
+      let start_pos = (point[1] as usize * self.info.stride + point[0] as usize) * self.info.bytes_per_pixel;
+      let bg_color = self.info.format.unpack(&self.buffer[start_pos..(start_pos + self.info.bytes_per_pixel)]);
+      self._draw_pixel(start_pos, color_with_alpha(color, bg_color, alpha));
+    }
+  }
+
+  //how much of a pixel is covered, as alpha, from how far inside the shape's edge (in pixels) its centre is
+  fn coverage(inside: f32) -> u8 {
+    ((inside + 0.5).clamp(0.0, 1.0) * 255.0).round() as u8
   }
 
   fn _draw_pixel(&mut self, start_pos: usize, color: RGBColor) {
-    let color = [color[2], color[1], color[0]];
-    let color = if self.grayscale { color_to_grayscale(color) } else { color };
-    self.buffer[start_pos..(start_pos + 3)]
-      .copy_from_slice(&color[..]);
+    let bytes_per_pixel = self.info.bytes_per_pixel;
+    let pixel = self.pixel_bytes(color);
+    self.buffer[start_pos..(start_pos + bytes_per_pixel)]
+      .copy_from_slice(&pixel[..bytes_per_pixel]);
   }
 
   //straight horizontal line
@@ -114,51 +332,131 @@ impl FramebufferWriter {
       .copy_from_slice(bytes);
   }
 
+  //straight horizontal line from x_start to x_end (exclusive), in pixels. may be off the buffer
+  fn _draw_span(&mut self, y: isize, x_start: isize, x_end: isize, color: RGBColor) {
+    if y < 0 || x_end <= 0 {
+      return;
+    }
+    let x_start = x_start.max(0) as usize;
+    if let Some((start, dimensions)) = self.clip_rect([x_start, y as usize], [(x_end as usize).saturating_sub(x_start), 1]) {
+      let line_bytes = self.pixel_bytes(color)[..self.info.bytes_per_pixel].repeat(dimensions[0]);
+      self._draw_line((start[1] * self.info.stride + start[0]) * self.info.bytes_per_pixel, &line_bytes[..]);
+    }
+  }
+
+  //the part of the buffer (src_width pixels wide) in the rect, which is in pixels relative to the buffer. top left is in
+  //pixels too. cut off at the edges of the buffer and the clip rect
+  fn _draw_buffer_rect(&mut self, top_left: Point, src_width: usize, rect: Rect, bytes: &[u8]) {
+    let bytes_per_pixel = self.info.bytes_per_pixel;
+    let src_height = bytes.len() / (src_width * bytes_per_pixel).max(1);
+    let dimensions = [rect.1[0].min(src_width.saturating_sub(rect.0[0])), rect.1[1].min(src_height.saturating_sub(rect.0[1]))];
+    if let Some((dest, dimensions)) = self.clip_rect([top_left[0] + rect.0[0], top_left[1] + rect.0[1]], dimensions) {
+      let bytes_per_line = dimensions[0] * bytes_per_pixel;
+      for y in 0..dimensions[1] {
+        //of the buffer we want to draw on
+        let start = ((dest[1] - top_left[1] + y) * src_width + dest[0] - top_left[0]) * bytes_per_pixel;
+        //for our framebuffer
+        let start_pos = ((dest[1] + y) * self.info.stride + dest[0]) * bytes_per_pixel;
+        self.buffer[start_pos..(start_pos + bytes_per_line)]
+          .copy_from_slice(&bytes[start..(start + bytes_per_line)]);
+      }
+    }
+  }
+
   pub fn draw_buffer(&mut self, top_left: Point, height: usize, bytes_per_line: usize, bytes: &[u8]) {
-    //for our framebuffer
-    let mut start_pos = (top_left[1] * self.info.stride + top_left[0]) * self.info.bytes_per_pixel;
-    //of the buffer we want to draw on
-    let mut start = 0;
-    for _y in 0..height {
-      self.buffer[start_pos..(start_pos + bytes_per_line)]
-        .copy_from_slice(&bytes[start..(start + bytes_per_line)]);
-      //let _ = unsafe { ptr::read_volatile(&self.buffer[start_pos]) };
-      start += bytes_per_line;
-      start_pos += self.info.stride * self.info.bytes_per_pixel;
+    let src_width = bytes_per_line / self.info.bytes_per_pixel;
+    self._draw_buffer_rect(self.scaled_point(top_left), src_width, ([0, 0], [src_width, height]), bytes);
+  }
+
+  //only part of the buffer, eg: the damaged part of a window. region is relative to the buffer, which is src_width pixels wide
+  pub fn draw_buffer_region(&mut self, top_left: Point, src_width: usize, region: Rect, bytes: &[u8]) {
+    self._draw_buffer_rect(self.scaled_point(top_left), src_width, self.scaled_rect(region.0, region.1), bytes);
+  }
+
+  //whole lines of the buffer the rects are on, as (start, length) in bytes, merged when they overlap or touch.
+  //copying lines is much simpler than copying rects, and still way less than the whole screen
+  pub fn damaged_byte_ranges(&self, rects: &[Rect]) -> Vec<(usize, usize)> {
+    let mut lines: Vec<(usize, usize)> = rects.iter().filter_map(|r| {
+      let r = self.scaled_rect(r.0, r.1);
+      let end = (r.0[1] + r.1[1]).min(self.info.height);
+      if r.0[1] < end { Some((r.0[1], end)) } else { None }
+    }).collect();
+    lines.sort();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in lines {
+      if let Some(last) = merged.last_mut() {
+        if start <= last.1 {
+          last.1 = last.1.max(end);
+          continue;
+        }
+      }
+      merged.push((start, end));
+    }
+    let bytes_per_line = self.info.stride * self.info.bytes_per_pixel;
+    merged.into_iter().map(|(start, end)| (start * bytes_per_line, (end - start) * bytes_per_line)).collect()
+  }
+
+  //nearest neighbour, eg: for thumbnails. dimensions are of the scaled buffer, which is cut off at bottom_right
+  //(exclusive) or the clip rect. src_dimensions are in pixels
+  pub fn draw_buffer_scaled(&mut self, top_left: Point, dimensions: Dimensions, bottom_right: Point, src_dimensions: Dimensions, bytes: &[u8]) {
+    let bytes_per_pixel = self.info.bytes_per_pixel;
+    let (top_left, dimensions) = self.scaled_rect(top_left, dimensions);
+    let bottom_right = self.scaled_point(bottom_right);
+    if dimensions[0] == 0 || dimensions[1] == 0 {
+      return;
+    }
+    let visible = [dimensions[0].min(bottom_right[0].saturating_sub(top_left[0])), dimensions[1].min(bottom_right[1].saturating_sub(top_left[1]))];
+    let Some((dest, visible)) = self.clip_rect(top_left, visible) else {
+      return;
+    };
+    for y in dest[1]..(dest[1] + visible[1]) {
+      let src_y = (y - top_left[1]) * src_dimensions[1] / dimensions[1];
+      let mut start_pos = (y * self.info.stride + dest[0]) * bytes_per_pixel;
+      for x in dest[0]..(dest[0] + visible[0]) {
+        let src_x = (x - top_left[0]) * src_dimensions[0] / dimensions[0];
+        let src_pos = (src_y * src_dimensions[0] + src_x) * bytes_per_pixel;
+        self.buffer[start_pos..(start_pos + bytes_per_pixel)]
+          .copy_from_slice(&bytes[src_pos..(src_pos + bytes_per_pixel)]);
+        start_pos += bytes_per_pixel;
+      }
     }
   }
 
+  //the font's chars are scaled (nearest neighbour) to the scale
   pub fn draw_char(&mut self, top_left: Point, char_info: &FontCharInfo, color: RGBColor, bg_color: RGBColor) {
-    let mut start_pos;
-    for row in 0..char_info.height {
-      start_pos = ((top_left[1] + row + char_info.top_offset as usize) * self.info.stride + top_left[0]) * self.info.bytes_per_pixel;
-      for col in &char_info.data[row] {
-        if col > &0 && start_pos + 3 < self.info.byte_len {
-          self._draw_pixel(start_pos, color_with_alpha(color, bg_color, *col));
+    let (top_left, dimensions) = self.scaled_rect([top_left[0], top_left[1] + char_info.top_offset as usize], [char_info.width, char_info.height]);
+    for y in 0..dimensions[1] {
+      let row = &char_info.data[y * char_info.height / dimensions[1]];
+      let row_width = self.scaled(row.len());
+      for x in 0..row_width {
+        let col = row[x * row.len() / row_width];
+        if col > 0 {
+          self._draw_pixel_at([(top_left[0] + x) as isize, (top_left[1] + y) as isize], color_with_alpha(color, bg_color, col));
         }
-        start_pos += self.info.bytes_per_pixel;
       }
     }
   }
 
   //dots
 
+  //a square of pixels, if the scale is more than 1
   pub fn draw_pixel(&mut self, point: Point, color: RGBColor) {
-    let start_pos = (point[1] * self.info.stride + point[0]) * self.info.bytes_per_pixel;
-    if self.info.byte_len > start_pos {
-      self._draw_pixel(start_pos, color);
+    let (top_left, dimensions) = self.scaled_rect(point, [1, 1]);
+    for y in 0..dimensions[1] {
+      for x in 0..dimensions[0] {
+        self._draw_pixel_at([(top_left[0] + x) as isize, (top_left[1] + y) as isize], color);
+      }
     }
   }
   
   //shapes
 
   pub fn draw_rect(&mut self, top_left: Point, dimensions: Dimensions, color: RGBColor) {
-    let color = if self.grayscale { color_to_grayscale(color) } else { color };
-    let line_bytes = if self.info.bytes_per_pixel > 3 {
-      [color[2], color[1], color[0], 255].repeat(dimensions[0])
-    } else {
-      color.repeat(dimensions[0])
+    let (top_left, dimensions) = self.scaled_rect(top_left, dimensions);
+    let Some((top_left, dimensions)) = self.clip_rect(top_left, dimensions) else {
+      return;
     };
+    let line_bytes = self.pixel_bytes(color)[..self.info.bytes_per_pixel].repeat(dimensions[0]);
     let mut start_pos = (top_left[1] * self.info.stride + top_left[0]) * self.info.bytes_per_pixel;
     for _row in 0..dimensions[1] {
       //use _draw_line instead for MUCH more efficiency
@@ -167,28 +465,71 @@ impl FramebufferWriter {
     }
   }
 
+  //blended with what is already there
+  pub fn draw_translucent_rect(&mut self, top_left: Point, dimensions: Dimensions, color: RGBAColor) {
+    if color[3] == 0 {
+      return;
+    } else if color[3] == 255 {
+      self.draw_rect(top_left, dimensions, [color[0], color[1], color[2]]);
+      return;
+    }
+    let (top_left, dimensions) = self.scaled_rect(top_left, dimensions);
+    let Some((top_left, dimensions)) = self.clip_rect(top_left, dimensions) else {
+      return;
+    };
+    let bytes_per_pixel = self.info.bytes_per_pixel;
+    for y in top_left[1]..(top_left[1] + dimensions[1]) {
+      let mut start_pos = (y * self.info.stride + top_left[0]) * bytes_per_pixel;
+      for _x in 0..dimensions[0] {
+        let bg_color = self.info.format.unpack(&self.buffer[start_pos..(start_pos + bytes_per_pixel)]);
+        self._draw_pixel(start_pos, color_with_alpha([color[0], color[1], color[2]], bg_color, color[3]));
+        start_pos += bytes_per_pixel;
+      }
+    }
+  }
+
   //can optimise (?) by turning into lines and doing _draw_line instead?
   pub fn draw_circle(&mut self, centre: Point, radius: usize, color: RGBColor) {
+    let centre = [self.scaled(centre[0]) as isize, self.scaled(centre[1]) as isize];
+    let radius = self.scaled(radius) as isize;
     //x^2 + y^2 <= r^2
     for y in 0..radius {
       for x in 0..radius {
         if (x.pow(2) + y.pow(2)) <= radius.pow(2) {
-          self.draw_pixel([centre[0] + x, centre[1] + y], color);
-          self.draw_pixel([centre[0] - x, centre[1] + y], color);
-          self.draw_pixel([centre[0] - x, centre[1] - y], color);
-          self.draw_pixel([centre[0] + x, centre[1] - y], color);
+          self._draw_pixel_at([centre[0] + x, centre[1] + y], color);
+          self._draw_pixel_at([centre[0] - x, centre[1] + y], color);
+          self._draw_pixel_at([centre[0] - x, centre[1] - y], color);
+          self._draw_pixel_at([centre[0] + x, centre[1] - y], color);
         }
       }
     }
   }
 
+  //edge pixels are blended by how much of them is inside the circle
+  pub fn draw_circle_anti_aliased(&mut self, centre: Point, radius: usize, color: RGBColor) {
+    //same centre and size as `draw_circle`
+    let centre = [self.scaled(centre[0]) as isize, self.scaled(centre[1]) as isize];
+    let radius = self.scaled(radius) as isize;
+    for y in -radius..=radius {
+      for x in -radius..=radius {
+        let distance = ((x.pow(2) + y.pow(2)) as f32).sqrt();
+        self._blend_pixel_at([centre[0] + x, centre[1] + y], color, FramebufferWriter::coverage(radius as f32 - 0.5 - distance));
+      }
+    }
+  }
+
   //direction is top to bottom
   pub fn draw_gradient(&mut self, top_left: Point, dimensions: Dimensions, start_color: RGBColor, end_color: RGBColor, steps: usize) {
     let delta_r = (end_color[0] as f32 - start_color[0] as f32) / steps as f32;
     let delta_g = (end_color[1] as f32 - start_color[1] as f32) / steps as f32;
     let delta_b = (end_color[2] as f32 - start_color[2] as f32) / steps as f32;
-    let mut start_pos = (top_left[1] * self.info.stride + top_left[0]) * self.info.bytes_per_pixel;
-    if steps <= dimensions[1] {
+    let (top_left, dimensions) = self.scaled_rect(top_left, dimensions);
+    //lines are cut off at the sides, and lines above or below the clip rect skipped
+    let Some((clip_top_left, clip_dimensions)) = self.clip_rect(top_left, dimensions) else {
+      return;
+    };
+    let mut y = top_left[1];
+    if steps > 0 && steps <= dimensions[1] {
       //rounds down
       let mut y_per = dimensions[1] / steps;
       for s in 0..steps {
@@ -200,20 +541,165 @@ impl FramebufferWriter {
         } else {
           color = [(start_color[0] as f32 + (delta_r * s as f32)) as u8, (start_color[1] as f32 + (delta_g * s as f32)) as u8, (start_color[2] as f32 + (delta_b * s as f32)) as u8];
         };
-        let color = if self.grayscale { color_to_grayscale(color) } else { color };
-        let line_bytes = if self.info.bytes_per_pixel > 3 {
-          [color[2], color[1], color[0], 255].repeat(dimensions[0])
-        } else {
-          color.repeat(dimensions[0])
-        };
+        let line_bytes = self.pixel_bytes(color)[..self.info.bytes_per_pixel].repeat(clip_dimensions[0]);
         for _y in 0..y_per {
-          self._draw_line(start_pos, &line_bytes[..]);
-          start_pos += self.info.stride * self.info.bytes_per_pixel;
+          if y >= clip_top_left[1] && y < clip_top_left[1] + clip_dimensions[1] {
+            self._draw_line((y * self.info.stride + clip_top_left[0]) * self.info.bytes_per_pixel, &line_bytes[..]);
+          }
+          y += 1;
+        }
+      }
+    }
+  }
+
+  //direction is left to right. the first step is the start colour and the last step is the end colour
+  pub fn draw_horizontal_gradient(&mut self, top_left: Point, dimensions: Dimensions, start_color: RGBColor, end_color: RGBColor, steps: usize) {
+    let (top_left, dimensions) = self.scaled_rect(top_left, dimensions);
+    let Some((clip_top_left, clip_dimensions)) = self.clip_rect(top_left, dimensions) else {
+      return;
+    };
+    if steps == 0 {
+      return;
+    }
+    let bytes_per_pixel = self.info.bytes_per_pixel;
+    //every line is the same
+    let mut line_bytes = Vec::with_capacity(clip_dimensions[0] * bytes_per_pixel);
+    for x in clip_top_left[0]..(clip_top_left[0] + clip_dimensions[0]) {
+      let step = ((x - top_left[0]) * steps / dimensions[0]).min(steps - 1);
+      let fraction = if steps > 1 { step as f32 / (steps - 1) as f32 } else { 0.0 };
+      let color = [0, 1, 2].map(|i| (start_color[i] as f32 + (end_color[i] as f32 - start_color[i] as f32) * fraction) as u8);
+      line_bytes.extend_from_slice(&self.pixel_bytes(color)[..bytes_per_pixel]);
+    }
+    let mut start_pos = (clip_top_left[1] * self.info.stride + clip_top_left[0]) * bytes_per_pixel;
+    for _y in 0..clip_dimensions[1] {
+      self._draw_line(start_pos, &line_bytes[..]);
+      start_pos += self.info.stride * bytes_per_pixel;
+    }
+  }
+
+  //the border is inside the rect
+  pub fn draw_rect_outline(&mut self, top_left: Point, dimensions: Dimensions, border_width: usize, color: RGBColor) {
+    let [width, height] = dimensions;
+    if border_width * 2 >= width || border_width * 2 >= height {
+      self.draw_rect(top_left, dimensions, color);
+      return;
+    }
+    self.draw_rect(top_left, [width, border_width], color);
+    self.draw_rect([top_left[0], top_left[1] + height - border_width], [width, border_width], color);
+    self.draw_rect([top_left[0], top_left[1] + border_width], [border_width, height - border_width * 2], color);
+    self.draw_rect([top_left[0] + width - border_width, top_left[1] + border_width], [border_width, height - border_width * 2], color);
+  }
+
+  //radius is shrunk if it doesn't fit
+  pub fn draw_rounded_rect(&mut self, top_left: Point, dimensions: Dimensions, radius: usize, color: RGBColor) {
+    let (top_left, dimensions) = self.scaled_rect(top_left, dimensions);
+    let radius = self.scaled(radius).min(dimensions[0] / 2).min(dimensions[1] / 2);
+    for y in 0..dimensions[1] {
+      //how many lines into a corner, counting from the top or bottom edge
+      let corner_y = if y < radius {
+        radius - y
+      } else if y + radius >= dimensions[1] {
+        y + radius + 1 - dimensions[1]
+      } else {
+        0
+      };
+      let inset = if corner_y > 0 {
+        let dy = corner_y as f32 - 0.5;
+        (radius as f32 - (radius.pow(2) as f32 - dy * dy).sqrt()).round() as usize
+      } else {
+        0
+      };
+      self._draw_span((top_left[1] + y) as isize, (top_left[0] + inset) as isize, (top_left[0] + dimensions[0] - inset) as isize, color);
+    }
+  }
+
+  //filled with the even-odd rule, so self intersecting polygons have holes. needs at least 3 points
+  pub fn draw_polygon(&mut self, points: &[Point], color: RGBColor) {
+    if points.len() < 3 {
+      return;
+    }
+    let points: Vec<[f32; 2]> = points.iter().map(|point| {
+      let point = self.scaled_point(*point);
+      [point[0] as f32, point[1] as f32]
+    }).collect();
+    let min_y = points.iter().map(|p| p[1]).fold(f32::MAX, f32::min) as isize;
+    let max_y = points.iter().map(|p| p[1]).fold(0.0, f32::max) as isize;
+    let mut crossings: Vec<f32> = Vec::new();
+    for y in min_y..max_y {
+      //through the centre of the pixels
+      let scan_y = y as f32 + 0.5;
+      crossings.clear();
+      for i in 0..points.len() {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        if (a[1] <= scan_y && scan_y < b[1]) || (b[1] <= scan_y && scan_y < a[1]) {
+          crossings.push(a[0] + (scan_y - a[1]) * (b[0] - a[0]) / (b[1] - a[1]));
+        }
+      }
+      crossings.sort_by(|a, b| a.total_cmp(b));
+      for pair in crossings.chunks(2) {
+        if let [start, end] = pair {
+          //pixels with their centre between the crossings
+          self._draw_span(y, (start - 0.5).ceil() as isize, (end - 0.5).ceil() as isize, color);
         }
       }
     }
   }
 
+  //the last point joins back to the first
+  pub fn draw_polygon_outline(&mut self, points: &[Point], width: usize, color: RGBColor) {
+    for i in 0..points.len() {
+      self.draw_line(points[i], points[(i + 1) % points.len()], width, color);
+    }
+  }
+
+  //pixels at most radius and more than radius - width from the centre (in pixels). if only part of the ring, the angles
+  //are in degrees clockwise from the right, and the part goes clockwise from the start angle to the end angle
+  fn _draw_ring(&mut self, centre: Point, radius: usize, width: usize, angles: Option<(u16, u16)>, color: RGBColor) {
+    let centre = [centre[0] as isize, centre[1] as isize];
+    let radius = radius as isize;
+    let inner = radius - (width as isize).max(1);
+    //360 degrees or more is the whole ring, and would be reduced to nothing (or a sliver) by the % 360 below
+    let angles = angles.filter(|(start, end)| (*end as u32) < *start as u32 + 360);
+    for dy in -radius..=radius {
+      for dx in -radius..=radius {
+        let distance = dx.pow(2) + dy.pow(2);
+        if distance > radius.pow(2) || (inner > 0 && distance <= inner.pow(2)) {
+          continue;
+        }
+        if let Some((start, end)) = angles {
+          let angle = (dy as f32).atan2(dx as f32).to_degrees().rem_euclid(360.0);
+          let (start, end) = ((start % 360) as f32, (end % 360) as f32);
+          let inside = if start <= end { angle >= start && angle <= end } else { angle >= start || angle <= end };
+          if !inside {
+            continue;
+          }
+        }
+        self._draw_pixel_at([centre[0] + dx, centre[1] + dy], color);
+      }
+    }
+  }
+
+  pub fn draw_circle_outline(&mut self, centre: Point, radius: usize, width: usize, color: RGBColor) {
+    self._draw_ring(self.scaled_point(centre), self.scaled(radius), self.scaled(width), None, color);
+  }
+
+  //angles are in degrees, clockwise from the right
+  pub fn draw_arc(&mut self, centre: Point, radius: usize, start_angle: u16, end_angle: u16, width: usize, color: RGBColor) {
+    self._draw_ring(self.scaled_point(centre), self.scaled(radius), self.scaled(width), Some((start_angle, end_angle)), color);
+  }
+
+  //radii is horizontal radius, vertical radius
+  pub fn draw_ellipse(&mut self, centre: Point, radii: Dimensions, color: RGBColor) {
+    let centre = [self.scaled(centre[0]) as isize, self.scaled(centre[1]) as isize];
+    let (radius_x, radius_y) = (self.scaled(radii[0]) as f32, self.scaled(radii[1]) as isize);
+    for y in -radius_y..radius_y {
+      //centre of the pixel, from -1 to 1
+      let ny = (y as f32 + 0.5) / radius_y as f32;
+      let half_width = radius_x * (1.0 - ny * ny).max(0.0).sqrt();
+      self._draw_span(centre[1] + y, centre[0] - half_width.round() as isize, centre[0] + half_width.round() as isize, color);
+    }
+  }
+
   //text
 
   pub fn draw_text(&mut self, top_left: Point, fonts: Vec<String>, text: &str, color: RGBColor, bg_color: RGBColor, horiz_spacing: usize, mono_width: Option<u8>) {
@@ -247,6 +733,9 @@ impl FramebufferWriter {
   //line
 
   pub fn draw_line(&mut self, start: Point, end: Point, width: usize, color: RGBColor) {
+    let start = self.scaled_point(start);
+    let end = self.scaled_point(end);
+    let width = self.scaled(width);
     //leftmost point
     let lm;
     let rm;
@@ -267,7 +756,31 @@ impl FramebufferWriter {
       let ix = if use_x { i } else { (m * i).round() };
       let iy = if use_x { (m * i).round() } else { i };
       for j in 0..width {
-        self.draw_pixel([(lm[0] as f64 + ix) as usize + j, (lm[1] as f64 + iy) as usize], color);
+        self._draw_pixel_at([(lm[0] as f64 + ix) as isize + j as isize, (lm[1] as f64 + iy) as isize], color);
+      }
+    }
+  }
+
+  //width is measured across the line, not horizontally like `draw_line`, and the ends are round.
+  //edge pixels are blended by how much of them is inside the line
+  pub fn draw_line_anti_aliased(&mut self, start: Point, end: Point, width: usize, color: RGBColor) {
+    let start = self.scaled_point(start);
+    let end = self.scaled_point(end);
+    let half_width = self.scaled(width).max(1) as f32 / 2.0;
+    let (x1, y1, x2, y2) = (start[0] as f32, start[1] as f32, end[0] as f32, end[1] as f32);
+    let length_squared = (x2 - x1).powi(2) + (y2 - y1).powi(2);
+    //every pixel that could be touched, inside the clip rect
+    let clip = self.clip();
+    let min_x = ((x1.min(x2) - half_width - 1.0).max(clip.0[0] as f32)) as usize;
+    let max_x = ((x1.max(x2) + half_width + 2.0) as usize).min(clip.0[0] + clip.1[0]);
+    let min_y = ((y1.min(y2) - half_width - 1.0).max(clip.0[1] as f32)) as usize;
+    let max_y = ((y1.max(y2) + half_width + 2.0) as usize).min(clip.0[1] + clip.1[1]);
+    for y in min_y..max_y {
+      for x in min_x..max_x {
+        //distance from the closest point of the line
+        let t = if length_squared == 0.0 { 0.0 } else { (((x as f32 - x1) * (x2 - x1) + (y as f32 - y1) * (y2 - y1)) / length_squared).clamp(0.0, 1.0) };
+        let distance = ((x as f32 - x1 - t * (x2 - x1)).powi(2) + (y as f32 - y1 - t * (y2 - y1)).powi(2)).sqrt();
+        self._blend_pixel_at([x as isize, y as isize], color, FramebufferWriter::coverage(half_width - distance));
       }
     }
   }
@@ -275,19 +788,28 @@ impl FramebufferWriter {
   //bmps
 
   //reverse is workaround for when my bmp lib returns rgba instead of bgra
-  pub fn draw_bmp(&mut self, top_left: Point, path: String, reverse: bool) {
+  //if transparent, pixels with 0 alpha are skipped. otherwise alpha is ignored, since it is often just unused
+  pub fn draw_bmp(&mut self, top_left: Point, path: String, reverse: bool, transparent: bool) {
     let b = BMP::new_from_file(&path);
     if let Ok(b) = b {
       let dib_header = b.get_dib_header().unwrap();
       let pixel_data = b.get_pixel_data().unwrap();
       let height = dib_header.height as usize;
       let width = dib_header.width as usize;
+      let (top_left, dimensions) = self.scaled_rect(top_left, [width, height]);
+      let Some((dest, visible)) = self.clip_rect(top_left, dimensions) else {
+        return;
+      };
       let mut start_pos;
-      for row in 0..height {
-        start_pos = ((top_left[1] + row) * self.info.stride + top_left[0]) * self.info.bytes_per_pixel;
-        for column in 0..width {
+      for y in dest[1]..(dest[1] + visible[1]) {
+        let row = (y - top_left[1]) * height / dimensions[1];
+        start_pos = (y * self.info.stride + dest[0]) * self.info.bytes_per_pixel;
+        for x in dest[0]..(dest[0] + visible[0]) {
+          let column = (x - top_left[0]) * width / dimensions[0];
           let color = b.get_color_of_pixel_efficient(column, row, &dib_header, &pixel_data).unwrap();
-          self._draw_pixel(start_pos, if reverse { [color[2], color[1], color[0]] } else { [color[0], color[1], color[2]] });
+          if !transparent || color[3] != 0 {
+            self._draw_pixel(start_pos, if reverse { [color[2], color[1], color[0]] } else { [color[0], color[1], color[2]] });
+          }
           start_pos += self.info.bytes_per_pixel;
         }
       }
@@ -295,3 +817,236 @@ impl FramebufferWriter {
   }
 }
 
+
+#[test]
+fn pixel_format_pack() {
+  let color = [0x12, 0x34, 0xF8];
+  //bgra (argb8888)
+  assert!(PixelFormat::default().pack(color) == [0xF8, 0x34, 0x12, 0xFF]);
+  //abgr8888
+  let abgr = PixelFormat { red: (0, 8), green: (8, 8), blue: (16, 8), transp: (24, 8) };
+  assert!(abgr.pack(color) == [0x12, 0x34, 0xF8, 0xFF]);
+  //rgb888, 3 bytes per pixel
+  let rgb888 = PixelFormat { transp: (0, 0), ..Default::default() };
+  assert!(rgb888.pack(color)[..3] == [0xF8, 0x34, 0x12]);
+  //rgb565, only the top bits of each colour are kept
+  let rgb565 = PixelFormat { red: (11, 5), green: (5, 6), blue: (0, 5), transp: (0, 0) };
+  assert!(rgb565.pack(color)[..2] == ((0x12u16 >> 3) << 11 | (0x34 >> 2) << 5 | 0xF8 >> 3).to_le_bytes());
+  assert!(rgb565.pack([255; 3])[..2] == [0xFF, 0xFF]);
+}
+
+#[test]
+fn draw_rect_rgb565() {
+  let mut writer = FramebufferWriter::new(false);
+  writer.init(FramebufferInfo {
+    byte_len: 4 * 2 * 2,
+    width: 4,
+    height: 2,
+    bytes_per_pixel: 2,
+    stride: 4,
+    old_stride: None,
+    format: PixelFormat { red: (11, 5), green: (5, 6), blue: (0, 5), transp: (0, 0) },
+  });
+  writer.draw_rect([1, 1], [2, 1], [255, 0, 0]);
+  assert!(writer.get_buffer() == [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0xF8, 0x00, 0xF8, 0, 0]);
+  writer.draw_pixel([3, 0], [0, 0, 255]);
+  assert!(writer.get_buffer()[6..8] == [0x1F, 0x00]);
+}
+
+#[test]
+fn rotation_round_trip() {
+  let dimensions = [5, 3];
+  for degrees in [0, 90, 180, 270] {
+    let rotation = Rotation::from_degrees(degrees).unwrap();
+    let screen_dimensions = if rotation.swaps_dimensions() { [3, 5] } else { dimensions };
+    for point in [[0, 0], [4, 0], [1, 2], [4, 2]] {
+      let on_screen = rotation.to_screen(point, dimensions);
+      assert!(on_screen[0] < screen_dimensions[0] && on_screen[1] < screen_dimensions[1]);
+      assert!(rotation.from_screen(on_screen, dimensions) == point);
+    }
+  }
+  //top left of the screen is bottom left of what is drawn, when turned 90 degrees
+  assert!(Rotation::Deg90.from_screen([0, 0], dimensions) == [4, 0]);
+}
+
+#[test]
+fn draw_rect_scaled() {
+  let mut writer = FramebufferWriter::new(false);
+  writer.init(FramebufferInfo {
+    byte_len: 5 * 2,
+    width: 5,
+    height: 2,
+    bytes_per_pixel: 1,
+    stride: 5,
+    old_stride: None,
+    format: PixelFormat { red: (0, 8), green: (0, 0), blue: (0, 0), transp: (0, 0) },
+  });
+  //1.5 pixels per unit. rects next to each other don't leave gaps
+  writer.set_scale(1.5);
+  writer.draw_rect([0, 0], [1, 1], [1, 0, 0]);
+  writer.draw_rect([1, 0], [1, 1], [2, 0, 0]);
+  writer.draw_rect([2, 0], [1, 1], [3, 0, 0]);
+  assert!(writer.get_buffer() == [1, 1, 2, 3, 3, 1, 1, 2, 3, 3]);
+}
+
+#[test]
+fn draw_clipped() {
+  let mut writer = FramebufferWriter::new(false);
+  writer.init(FramebufferInfo {
+    byte_len: 4 * 2,
+    width: 4,
+    height: 2,
+    bytes_per_pixel: 1,
+    stride: 4,
+    old_stride: None,
+    format: PixelFormat { red: (0, 8), green: (0, 0), blue: (0, 0), transp: (0, 0) },
+  });
+  writer.push_clip([1, 0], [2, 5]);
+  writer.draw_rect([0, 0], [4, 2], [1, 0, 0]);
+  //nested clips only draw where they overlap
+  writer.push_clip([2, 1], [5, 5]);
+  writer.draw_rect([0, 0], [4, 2], [2, 0, 0]);
+  writer.pop_clip();
+  writer.pop_clip();
+  assert!(writer.get_buffer() == [0, 1, 1, 0, 0, 1, 2, 0]);
+  //near and past the edges, so would have wrapped onto the next line or underflowed
+  writer.draw_circle([0, 0], 3, [3, 0, 0]);
+  writer.draw_line([2, 1], [9, 1], 3, [4, 0, 0]);
+  assert!(writer.get_buffer() == [3, 3, 3, 0, 3, 3, 4, 4]);
+}
+
+#[test]
+fn draw_polygon_and_outline() {
+  let mut writer = FramebufferWriter::new(false);
+  writer.init(FramebufferInfo {
+    byte_len: 4 * 4,
+    width: 4,
+    height: 4,
+    bytes_per_pixel: 1,
+    stride: 4,
+    old_stride: None,
+    format: PixelFormat { red: (0, 8), green: (0, 0), blue: (0, 0), transp: (0, 0) },
+  });
+  //pixels whose centres are inside
+  writer.draw_polygon(&[[0, 0], [4, 0], [0, 4]], [1, 0, 0]);
+  assert!(writer.get_buffer() == [1, 1, 1, 0, 1, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+  writer.draw_rect_outline([0, 0], [4, 4], 1, [2, 0, 0]);
+  assert!(writer.get_buffer() == [2, 2, 2, 2, 2, 1, 0, 2, 2, 0, 0, 2, 2, 2, 2, 2]);
+}
+
+#[test]
+fn draw_anti_aliased() {
+  //unpacking is the reverse of packing, apart from the bits lost
+  let rgb565 = PixelFormat { red: (11, 5), green: (5, 6), blue: (0, 5), transp: (0, 0) };
+  assert!(rgb565.unpack(&rgb565.pack([255, 0, 255])[..2]) == [255, 0, 255]);
+  assert!(PixelFormat::default().unpack(&PixelFormat::default().pack([12, 34, 56])) == [12, 34, 56]);
+  let mut writer = FramebufferWriter::new(false);
+  writer.init(FramebufferInfo {
+    byte_len: 5 * 5 * 4,
+    width: 5,
+    height: 5,
+    bytes_per_pixel: 4,
+    stride: 5,
+    old_stride: None,
+    format: Default::default(),
+  });
+  writer.draw_line_anti_aliased([0, 0], [4, 4], 1, [255, 255, 255]);
+  let pixel = |writer: &mut FramebufferWriter, x: usize, y: usize| PixelFormat::default().unpack(&writer.get_buffer()[(y * 5 + x) * 4..(y * 5 + x) * 4 + 4])[0];
+  //on the line is fully covered, next to it is partly, further away isn't at all
+  assert!(pixel(&mut writer, 2, 2) == 255);
+  assert!(pixel(&mut writer, 3, 2) > 0 && pixel(&mut writer, 3, 2) < 255);
+  assert!(pixel(&mut writer, 4, 0) == 0);
+}
+
+#[test]
+fn draw_translucent() {
+  let mut writer = FramebufferWriter::new(false);
+  writer.init(FramebufferInfo {
+    byte_len: 2 * 4,
+    width: 2,
+    height: 1,
+    bytes_per_pixel: 4,
+    stride: 2,
+    old_stride: None,
+    format: Default::default(),
+  });
+  writer.draw_rect([0, 0], [2, 1], [200, 100, 0]);
+  writer.draw_translucent_rect([1, 0], [1, 1], [0, 0, 255, 128]);
+  let buffer = writer.get_buffer().to_vec();
+  assert!(PixelFormat::default().unpack(&buffer[..4]) == [200, 100, 0]);
+  assert!(PixelFormat::default().unpack(&buffer[4..]) == [99, 49, 128]);
+}
+
+#[test]
+fn draw_bmp_alpha() {
+  //2x1, 32 bit BI_RGB, with 0 in the alpha byte like a lot of opaque BMPs
+  let mut bmp = Vec::new();
+  bmp.extend(b"BM");
+  bmp.extend(62u32.to_le_bytes());
+  bmp.extend([0; 4]);
+  bmp.extend(54u32.to_le_bytes());
+  bmp.extend(40u32.to_le_bytes());
+  bmp.extend(2i32.to_le_bytes());
+  bmp.extend(1i32.to_le_bytes());
+  bmp.extend(1u16.to_le_bytes());
+  bmp.extend(32u16.to_le_bytes());
+  bmp.extend([0; 24]);
+  bmp.extend([10, 20, 30, 0, 40, 50, 60, 0]);
+  let path = std::env::temp_dir().join(format!("ming-wm-draw-bmp-{}.bmp", std::process::id()));
+  std::fs::write(&path, bmp).unwrap();
+  let path = path.to_string_lossy().to_string();
+  let mut writer = FramebufferWriter::new(false);
+  writer.init(FramebufferInfo {
+    byte_len: 4 * 2,
+    width: 2,
+    height: 1,
+    bytes_per_pixel: 4,
+    stride: 2,
+    old_stride: None,
+    format: Default::default(),
+  });
+  //not transparent, so drawn anyways
+  writer.draw_bmp([0, 0], path.clone(), false, false);
+  let buffer = writer.get_buffer().to_vec();
+  assert!(PixelFormat::default().unpack(&buffer[..4]) == [10, 20, 30]);
+  assert!(PixelFormat::default().unpack(&buffer[4..]) == [40, 50, 60]);
+  //transparent, so skipped
+  writer.draw_rect([0, 0], [2, 1], [0, 0, 0]);
+  writer.draw_bmp([0, 0], path.clone(), true, true);
+  let buffer = writer.get_buffer().to_vec();
+  assert!(PixelFormat::default().unpack(&buffer[..4]) == [0, 0, 0]);
+  assert!(PixelFormat::default().unpack(&buffer[4..]) == [0, 0, 0]);
+  std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn draw_arc_spans() {
+  let mut writer = FramebufferWriter::new(false);
+  let info = FramebufferInfo {
+    byte_len: 21 * 21,
+    width: 21,
+    height: 21,
+    bytes_per_pixel: 1,
+    stride: 21,
+    old_stride: None,
+    format: PixelFormat { red: (0, 8), green: (0, 0), blue: (0, 0), transp: (0, 0) },
+  };
+  writer.init(info.clone());
+  writer.draw_circle_outline([10, 10], 8, 1, [1, 0, 0]);
+  let circle = writer.get_buffer().to_vec();
+  //full circle
+  writer.init(info.clone());
+  writer.draw_arc([10, 10], 8, 0, 360, 1, [1, 0, 0]);
+  assert!(writer.get_buffer() == circle);
+  writer.init(info.clone());
+  writer.draw_arc([10, 10], 8, 90, 540, 1, [1, 0, 0]);
+  assert!(writer.get_buffer() == circle);
+  //wraps past 0, so top, right and bottom but not left
+  writer.init(info);
+  writer.draw_arc([10, 10], 8, 270, 90, 1, [1, 0, 0]);
+  let buffer = writer.get_buffer();
+  assert!(buffer[10 * 21 + 18] == 1);
+  assert!(buffer[2 * 21 + 10] == 1);
+  assert!(buffer[18 * 21 + 10] == 1);
+  assert!(buffer[10 * 21 + 2] == 0);
+}
@@ -3,6 +3,10 @@ pub use linux;
 pub mod framebuffer;
 pub mod window_manager;
 pub mod fs;
+pub mod config;
 mod proxy_window_like;
+mod menu;
+mod clipboard;
+mod retained;
 mod essential;
 
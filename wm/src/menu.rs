@@ -0,0 +1,177 @@
+use std::vec;
+use std::vec::Vec;
+
+use ming_wm_lib::window_manager_types::{ DrawInstructions, MenuItem };
+use ming_wm_lib::messages::KeyPress;
+use ming_wm_lib::themes::ThemeInfo;
+use ming_wm_lib::framebuffer_types::{ Dimensions, Point };
+
+pub const BAR_HEIGHT: usize = 20;
+const ITEM_HEIGHT: usize = 20;
+const MONO_WIDTH: usize = 10;
+const PADDING: usize = 6;
+
+pub enum MenuResult {
+  Nothing,
+  Redraw,
+  Close,
+  Action(String),
+}
+
+//the menu bar of a window, while it is open. drawn by the window manager on top of the window,
+//right under the window top
+pub struct OpenMenu {
+  pub id: usize, //of the window it is for
+  items: Vec<MenuItem>,
+  path: Vec<usize>, //index of the selected item at each level, first is the top level (the bar itself)
+}
+
+impl OpenMenu {
+  pub fn new(id: usize, items: Vec<MenuItem>) -> Self {
+    let mut menu = Self {
+      id,
+      items,
+      path: vec![0],
+    };
+    menu.open_first_dropdown();
+    menu
+  }
+
+  fn open_first_dropdown(&mut self) {
+    if !self.items[self.path[0]].children.is_empty() {
+      self.path.push(0);
+    }
+  }
+
+  //items at that level, the children of the selected item of the level above
+  fn level_items(&self, level: usize) -> &[MenuItem] {
+    let mut items = &self.items[..];
+    for l in 0..level {
+      items = &items[self.path[l]].children;
+    }
+    items
+  }
+
+  fn selected(&self) -> &MenuItem {
+    let level = self.path.len() - 1;
+    &self.level_items(level)[self.path[level]]
+  }
+
+  fn switch_top(&mut self, forwards: bool) {
+    let len = self.items.len();
+    self.path = vec![if forwards { (self.path[0] + 1) % len } else { (self.path[0] + len - 1) % len }];
+    self.open_first_dropdown();
+  }
+
+  pub fn handle_key(&mut self, key_press: KeyPress) -> MenuResult {
+    if key_press.is_escape() {
+      MenuResult::Close
+    } else if key_press.key == 'h' || key_press.is_left_arrow() {
+      //back out of submenus, or go to the previous menu
+      if self.path.len() > 2 {
+        self.path.pop();
+      } else {
+        self.switch_top(false);
+      }
+      MenuResult::Redraw
+    } else if key_press.key == 'l' || key_press.is_right_arrow() {
+      //into submenus, or go to the next menu
+      if self.path.len() > 1 && !self.selected().children.is_empty() {
+        self.path.push(0);
+      } else {
+        self.switch_top(true);
+      }
+      MenuResult::Redraw
+    } else if key_press.key == 'j' || key_press.is_down_arrow() || key_press.key == 'k' || key_press.is_up_arrow() {
+      let level = self.path.len() - 1;
+      if level > 0 {
+        let len = self.level_items(level).len();
+        self.path[level] = if key_press.key == 'j' || key_press.is_down_arrow() {
+          (self.path[level] + 1) % len
+        } else {
+          (self.path[level] + len - 1) % len
+        };
+      }
+      MenuResult::Redraw
+    } else if key_press.is_enter() {
+      if self.selected().children.is_empty() {
+        MenuResult::Action(self.selected().action.clone())
+      } else {
+        self.path.push(0);
+        MenuResult::Redraw
+      }
+    } else {
+      MenuResult::Nothing
+    }
+  }
+
+  //relative to the window. the top is the bottom of the window top
+  pub fn draw(&self, window_dimensions: Dimensions, top: usize, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    let (mut instructions, mut dropdown_x) = draw_bar(&self.items, Some(self.path[0]), window_dimensions, top, theme_info);
+    //a dropdown for each open level, submenus to the right of their parent item
+    let mut dropdown_y = top + BAR_HEIGHT;
+    for level in 1..self.path.len() {
+      let items = self.level_items(level);
+      let width = items.iter().map(|item| {
+        let shortcut_len = item.shortcut.as_ref().map_or(0, |s| s.chars().count() + 2);
+        let arrow_len = if item.children.is_empty() { 0 } else { 2 };
+        (item.label.chars().count() + shortcut_len + arrow_len) * MONO_WIDTH
+      }).max().unwrap_or(0) + PADDING * 2;
+      let height = items.len() * ITEM_HEIGHT + 2;
+      if level > 1 {
+        dropdown_y += self.path[level - 1] * ITEM_HEIGHT;
+      }
+      //keep inside the window if possible
+      dropdown_x = dropdown_x.min(window_dimensions[0].saturating_sub(width + 1));
+      instructions.extend(vec![
+        DrawInstructions::Rect([dropdown_x, dropdown_y], [width, height], theme_info.border_right_bottom),
+        DrawInstructions::Rect([dropdown_x, dropdown_y], [width - 1, height - 1], theme_info.border_left_top),
+        DrawInstructions::Rect([dropdown_x + 1, dropdown_y + 1], [width - 2, height - 2], theme_info.background),
+      ]);
+      for (i, item) in items.iter().enumerate() {
+        let item_y = dropdown_y + 1 + i * ITEM_HEIGHT;
+        let (color, bg_color) = if i == self.path[level] {
+          instructions.push(DrawInstructions::Rect([dropdown_x + 1, item_y], [width - 2, ITEM_HEIGHT], theme_info.top));
+          (theme_info.top_text, theme_info.top)
+        } else {
+          (theme_info.text, theme_info.background)
+        };
+        instructions.push(text([dropdown_x + PADDING, item_y + 2], item.label.clone(), color, bg_color));
+        let right = if item.children.is_empty() { item.shortcut.clone().unwrap_or_default() } else { ">".to_string() };
+        if !right.is_empty() {
+          let right_x = dropdown_x + width - PADDING - right.chars().count() * MONO_WIDTH;
+          instructions.push(text([right_x, item_y + 2], right, color, bg_color));
+        }
+      }
+      dropdown_x += width;
+    }
+    instructions
+  }
+}
+
+fn text(top_left: Point, text: String, color: [u8; 3], bg_color: [u8; 3]) -> DrawInstructions {
+  DrawInstructions::Text(top_left, vec!["nimbus-romono".to_string()], text, color, bg_color, Some(0), Some(MONO_WIDTH as u8))
+}
+
+//the bar itself, which is always shown for windows with a menu, and the x of the selected item, if any
+pub fn draw_bar(items: &[MenuItem], selected: Option<usize>, window_dimensions: Dimensions, top: usize, theme_info: &ThemeInfo) -> (Vec<DrawInstructions>, usize) {
+  let mut instructions = vec![
+    DrawInstructions::Rect([1, top], [window_dimensions[0] - 2, BAR_HEIGHT], theme_info.background),
+    DrawInstructions::Rect([1, top + BAR_HEIGHT - 1], [window_dimensions[0] - 2, 1], theme_info.border_right_bottom),
+  ];
+  let mut x = 1;
+  let mut selected_x = 1;
+  for (i, item) in items.iter().enumerate() {
+    let width = item.label.chars().count() * MONO_WIDTH + PADDING * 2;
+    let (color, bg_color) = if selected == Some(i) {
+      selected_x = x;
+      instructions.push(DrawInstructions::Rect([x, top], [width, BAR_HEIGHT - 1], theme_info.top));
+      (theme_info.top_text, theme_info.top)
+    } else {
+      (theme_info.text, theme_info.background)
+    };
+    instructions.push(text([x + PADDING, top + 2], item.label.clone(), color, bg_color));
+    x += width;
+  }
+  (instructions, selected_x)
+}
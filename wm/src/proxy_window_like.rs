@@ -1,101 +1,232 @@
 use std::vec::Vec;
-use std::process::{ Command, Child, Stdio };
+use std::process::{ Command, Child, ChildStdout, Stdio };
 use std::io::{ BufReader, BufRead, Write };
 use std::cell::RefCell;
+use std::rc::Rc;
 
-use ming_wm_lib::window_manager_types::{ DrawInstructions, WindowLike, WindowLikeType };
+use ming_wm_lib::window_manager_types::{ DrawInstructions, WindowLike, WindowLikeType, SizeHints, MenuItem };
 use ming_wm_lib::messages::{ WindowMessage, WindowMessageResponse };
-use ming_wm_lib::framebuffer_types::Dimensions;
+use ming_wm_lib::framebuffer_types::{ Dimensions, Rect };
 use ming_wm_lib::themes::ThemeInfo;
 use ming_wm_lib::dirs;
-use ming_wm_lib::serialize::{ Serializable, DrawInstructionsVec };
+use ming_wm_lib::ipc::CAPABILITIES_ENV;
+use ming_wm_lib::serialize::{ Serializable, DrawInstructionsVec, MenuItemsVec, Damage };
+
+//shared by all the windows of a process
+pub struct ProxyProcess {
+  child: RefCell<Child>,
+  //one reader for the life of the process, a new one per line could drop lines it buffered
+  stdout: RefCell<Option<BufReader<ChildStdout>>>,
+  name: String, //executable name
+  capabilities: RefCell<Option<Vec<String>>>, //None until the first line is read
+}
+
+//kill process when the last window using it is dropped
+impl Drop for ProxyProcess {
+  fn drop(&mut self) {
+    let _ = self.child.borrow_mut().kill();
+  }
+}
 
 pub struct ProxyWindowLike {
-  process: RefCell<Child>,
+  process: Rc<ProxyProcess>,
+  window_id: usize, //0 for the first window of the process
 }
 
 //try to handle panics of child processes so the entire wm doesn't crash
 impl WindowLike for ProxyWindowLike {
   fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
-    if let Some(stdin) = self.process.borrow_mut().stdin.as_mut() {
-      let _ = stdin.write_all(("handle_message ".to_string() + &message.serialize() + "\n").as_bytes());
-    }
+    self.write_line(&("handle_message ".to_string() + &message.serialize()));
     let output = self.read_line();
     WindowMessageResponse::deserialize(&output).unwrap_or(WindowMessageResponse::JustRedraw)
   }
 
   fn draw(&self, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
-    if let Some(stdin) = self.process.borrow_mut().stdin.as_mut() {
-      let _ = stdin.write_all(("draw ".to_string() + &theme_info.serialize() + "\n").as_bytes());
-    }
+    self.write_line(&("draw ".to_string() + &theme_info.serialize()));
     let output = self.read_line();
     DrawInstructionsVec::deserialize(&output).unwrap_or(Vec::new())
   }
 
   //properties
   fn title(&self) -> String {
-    if let Some(stdin) = self.process.borrow_mut().stdin.as_mut() {
-      let _ = stdin.write_all("title\n".as_bytes());
-    }
+    self.write_line("title");
     self.read_line().chars().filter(|c| *c != '\n').collect()
   }
 
   fn resizable(&self) -> bool {
     //serialize for bool is just true -> "true", false -> "false"
-    if let Some(stdin) = self.process.borrow_mut().stdin.as_mut() {
-      let _ = stdin.write_all("resizable\n".to_string().as_bytes());
-    }
+    self.write_line("resizable");
     let output = self.read_line();
     output == "true\n"
   }
 
-  fn subtype(&self) -> WindowLikeType {
-    if let Some(stdin) = self.process.borrow_mut().stdin.as_mut() {
-      let _ = stdin.write_all("subtype\n".to_string().as_bytes());
+  fn size_hints(&self) -> SizeHints {
+    if !self.supports("size_hints") {
+      return Default::default();
     }
+    self.write_line("size_hints");
     let output = self.read_line();
-    WindowLikeType::deserialize(&output).unwrap_or(WindowLikeType::Window)
+    SizeHints::deserialize(&output).unwrap_or_default()
   }
 
-  fn ideal_dimensions(&self, dimensions: Dimensions) -> Dimensions {
-    if let Some(stdin) = self.process.borrow_mut().stdin.as_mut() {
-      let _ = stdin.write_all(("ideal_dimensions ".to_string() + &dimensions.serialize() + "\n").as_bytes());
+  fn icon(&self) -> Option<String> {
+    let output = if self.supports("icon") {
+      self.write_line("icon");
+      self.read_line()
+    } else {
+      String::new()
+    };
+    if let Some(path) = output.strip_suffix("\n").unwrap_or(&output).strip_prefix("S") {
+      Some(path.to_string())
+    } else {
+      //fall back to the icon installed for the executable, if there is one
+      let path = dirs::exe_dir(Some(&format!("ming_bmps/icons/{}.bmp", self.process.name)));
+      if path.is_file() {
+        Some(path.to_string_lossy().to_string())
+      } else {
+        None
+      }
+    }
+  }
+
+  fn menu(&self) -> Vec<MenuItem> {
+    if !self.supports("menu") {
+      return Vec::new();
     }
+    self.write_line("menu");
     let output = self.read_line();
-    Dimensions::deserialize(&output).unwrap_or([420, 420])
+    MenuItemsVec::deserialize(&output).unwrap_or_default()
   }
-}
 
-//kill process when this window like dropped
-impl Drop for ProxyWindowLike {
-  fn drop(&mut self) {
-    let _ = self.process.borrow_mut().kill();
+  fn damage(&self) -> Option<Vec<Rect>> {
+    if !self.supports("damage") {
+      return None;
+    }
+    self.write_line("damage");
+    let output = self.read_line();
+    Damage::deserialize(&output).unwrap_or(None)
+  }
+
+  fn subtype(&self) -> WindowLikeType {
+    self.write_line("subtype");
+    let output = self.read_line();
+    WindowLikeType::deserialize(&output).unwrap_or(WindowLikeType::Window)
+  }
+
+  fn ideal_dimensions(&self, dimensions: Dimensions) -> Dimensions {
+    self.write_line(&("ideal_dimensions ".to_string() + &dimensions.serialize()));
+    let output = self.read_line();
+    Dimensions::deserialize(&output).unwrap_or([420, 420])
   }
 }
 
 impl ProxyWindowLike {
   pub fn new(name: &str) -> Self {
     let loc = dirs::exe_dir(Some(name)).to_string_lossy().to_string();
+    ProxyWindowLike::spawn(Command::new(loc), name)
+  }
+
+  fn spawn(mut command: Command, name: &str) -> Self {
+    let mut child = command.env(CAPABILITIES_ENV, "1").stdout(Stdio::piped()).stdin(Stdio::piped()).stderr(Stdio::null()).spawn().unwrap();
+    let stdout = child.stdout.take().map(BufReader::new);
     ProxyWindowLike {
-      process: RefCell::new(Command::new(loc).stdout(Stdio::piped()).stdin(Stdio::piped()).stderr(Stdio::null()).spawn().unwrap()),
+      process: Rc::new(ProxyProcess {
+        child: RefCell::new(child),
+        stdout: RefCell::new(stdout),
+        name: name.to_string(),
+        capabilities: RefCell::new(None),
+      }),
+      window_id: 0,
+    }
+  }
+
+  /// Another window of the same process
+  pub fn new_additional(process: &Rc<ProxyProcess>, window_id: usize) -> Self {
+    ProxyWindowLike {
+      process: Rc::clone(process),
+      window_id,
+    }
+  }
+
+  pub fn process(&self) -> &Rc<ProxyProcess> {
+    &self.process
+  }
+
+  //lines for windows that aren't the first are tagged with the window id, so the first window
+  //works with processes that only have one window
+  fn write_line(&self, line: &str) {
+    if let Some(stdin) = self.process.child.borrow_mut().stdin.as_mut() {
+      let tag = if self.window_id == 0 { String::new() } else { format!("@{} ", self.window_id) };
+      let _ = stdin.write_all((tag + line + "\n").as_bytes());
     }
   }
 
   //return empty string if error, do not propogate Err becuase that's messy
   //or maybe return "panicked"?
   fn read_line(&self) -> String {
-    let mut buffer = self.process.borrow_mut();
-    if let Some(buffer) = buffer.stdout.as_mut() {
-      let mut output = String::new();
-      let mut reader = BufReader::new(buffer);
-      if reader.read_line(&mut output).is_ok() {
-        output
-      } else {
-        String::new()
-      }
+    let output = self.read_raw_line();
+    let mut capabilities = self.process.capabilities.borrow_mut();
+    if capabilities.is_some() {
+      return output;
+    }
+    //newer windows start by listing the methods they support (see `ming_wm_lib::ipc::CAPABILITIES`) before responding
+    //to anything. for older windows, the first line is already a response
+    if let Some(methods) = output.strip_suffix("\n").unwrap_or(&output).strip_prefix("capabilities ") {
+      *capabilities = Some(methods.split(" ").map(|m| m.to_string()).collect());
+      self.read_raw_line()
     } else {
-      String::new()
+      *capabilities = Some(Vec::new());
+      output
     }
   }
+
+  fn read_raw_line(&self) -> String {
+    let mut output = String::new();
+    if let Some(reader) = self.process.stdout.borrow_mut().as_mut() {
+      if reader.read_line(&mut output).is_err() {
+        output = String::new();
+      }
+    }
+    output
+  }
+
+  //older windows never respond to methods they don't know, so reading a response would block forever
+  fn supports(&self, method: &str) -> bool {
+    if self.process.capabilities.borrow().is_none() {
+      //any query will do, the capabilities come before its response
+      self.write_line("subtype");
+      self.read_line();
+    }
+    self.process.capabilities.borrow().as_ref().is_some_and(|c| c.iter().any(|m| m == method))
+  }
 }
 
+#[test]
+fn proxy_capabilities() {
+  let hints = SizeHints { min: Some([200, 100]), ..Default::default() };
+  //older windows only answer what they know, so size_hints must not be sent (the read would never return)
+  let mut old = Command::new("sh");
+  old.arg("-c").arg("read l; echo Window; read l; echo Old");
+  let old = ProxyWindowLike::spawn(old, "old");
+  assert!(old.size_hints() == SizeHints::default());
+  assert!(old.title() == "Old");
+  //newer windows list what they support first, even if the response to the first query comes with it
+  let mut new = Command::new("sh");
+  new.arg("-c").arg(format!("echo capabilities size_hints; read l; echo Window; read l; echo '{}'; read l; echo New", hints.serialize()));
+  let new = ProxyWindowLike::spawn(new, "new");
+  assert!(new.size_hints() == hints);
+  assert!(new.title() == "New");
+}
+
+#[test]
+fn proxy_additional_windows_are_tagged() {
+  //echoes back what it is sent
+  let mut command = Command::new("sh");
+  command.arg("-c").arg("while read l; do echo \"$l\"; done");
+  let first = ProxyWindowLike::spawn(command, "echo");
+  let second = ProxyWindowLike::new_additional(first.process(), 2);
+  assert!(first.title() == "title");
+  assert!(second.title() == "@2 title");
+  //same process, so the responses stay in order
+  assert!(first.title() == "title");
+}
@@ -0,0 +1,152 @@
+use std::vec::Vec;
+use std::cell::{ Ref, RefCell };
+
+use ming_wm_lib::window_manager_types::{ DrawInstructions, WindowLike, WindowLikeType, SizeHints, MenuItem };
+use ming_wm_lib::messages::{ WindowBox, WindowMessage, WindowMessageResponse };
+use ming_wm_lib::framebuffer_types::{ Dimensions, Rect };
+use ming_wm_lib::themes::ThemeInfo;
+
+//a window-like and the last thing it drew (without the window decorations), already rasterised, so windows that
+//haven't changed don't need to be asked to draw again (which could be ipc) and drawn again.
+//it is thrown out when the window-like responds to a message with anything other than DoNothing, and is only used
+//if the window-like is the same size. the window manager throws them all out if the theme changes.
+//window-likes whose drawing changes without being sent a message (eg: a clock) are never retained
+pub struct RetainedWindowLike {
+  window_like: WindowBox,
+  surface: RefCell<Option<(Dimensions, Vec<u8>)>>,
+  retain: bool,
+}
+
+impl WindowLike for RetainedWindowLike {
+  fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
+    let response = self.window_like.handle_message(message);
+    if response != WindowMessageResponse::DoNothing {
+      self.invalidate();
+    }
+    response
+  }
+
+  fn draw(&self, theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    self.window_like.draw(theme_info)
+  }
+
+  //properties
+  fn title(&self) -> String {
+    self.window_like.title()
+  }
+
+  fn resizable(&self) -> bool {
+    self.window_like.resizable()
+  }
+
+  fn size_hints(&self) -> SizeHints {
+    self.window_like.size_hints()
+  }
+
+  fn icon(&self) -> Option<String> {
+    self.window_like.icon()
+  }
+
+  fn menu(&self) -> Vec<MenuItem> {
+    self.window_like.menu()
+  }
+
+  fn damage(&self) -> Option<Vec<Rect>> {
+    self.window_like.damage()
+  }
+
+  fn subtype(&self) -> WindowLikeType {
+    self.window_like.subtype()
+  }
+
+  fn ideal_dimensions(&self, dimensions: Dimensions) -> Dimensions {
+    self.window_like.ideal_dimensions(dimensions)
+  }
+}
+
+impl RetainedWindowLike {
+  pub fn new(window_like: WindowBox, retain: bool) -> Self {
+    Self {
+      window_like,
+      surface: RefCell::new(None),
+      retain,
+    }
+  }
+
+  pub fn surface(&self, dimensions: Dimensions) -> Option<Ref<'_, Vec<u8>>> {
+    Ref::filter_map(self.surface.borrow(), |surface| {
+      surface.as_ref().filter(|s| s.0 == dimensions).map(|s| &s.1)
+    }).ok()
+  }
+
+  pub fn set_surface(&self, dimensions: Dimensions, bytes: Vec<u8>) {
+    if self.retain {
+      *self.surface.borrow_mut() = Some((dimensions, bytes));
+    }
+  }
+
+  pub fn invalidate(&self) {
+    *self.surface.borrow_mut() = None;
+  }
+}
+
+//r redraws, anything else does nothing. counts how many times it is asked to draw
+#[cfg(test)]
+pub(crate) struct DrawCounter {
+  pub draws: std::rc::Rc<RefCell<usize>>,
+}
+
+#[cfg(test)]
+impl WindowLike for DrawCounter {
+  fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
+    match message {
+      WindowMessage::KeyPress(key_press) if key_press.key == 'r' => WindowMessageResponse::JustRedraw,
+      _ => WindowMessageResponse::DoNothing,
+    }
+  }
+
+  fn draw(&self, _theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    *self.draws.borrow_mut() += 1;
+    Vec::new()
+  }
+
+  fn title(&self) -> String {
+    "Draws".to_string()
+  }
+
+  fn subtype(&self) -> WindowLikeType {
+    WindowLikeType::Window
+  }
+
+  fn ideal_dimensions(&self, _dimensions: Dimensions) -> Dimensions {
+    [100, 100]
+  }
+}
+
+#[test]
+fn retained_surface_invalidation() {
+  use ming_wm_lib::messages::KeyPress;
+
+  let draws = std::rc::Rc::new(RefCell::new(0));
+  let mut retained = RetainedWindowLike::new(Box::new(DrawCounter { draws: draws.clone() }), true);
+  assert!(retained.surface([100, 100]).is_none());
+  retained.set_surface([100, 100], vec![1, 2, 3]);
+  assert!(retained.surface([100, 100]).is_some_and(|s| *s == vec![1, 2, 3]));
+  //only for the same size
+  assert!(retained.surface([100, 120]).is_none());
+  //kept if nothing changed
+  retained.handle_message(WindowMessage::KeyPress(KeyPress { key: 'a' }));
+  assert!(retained.surface([100, 100]).is_some());
+  //thrown out if it did
+  retained.handle_message(WindowMessage::KeyPress(KeyPress { key: 'r' }));
+  assert!(retained.surface([100, 100]).is_none());
+  retained.set_surface([100, 100], vec![1, 2, 3]);
+  retained.invalidate();
+  assert!(retained.surface([100, 100]).is_none());
+  //never kept if not retained
+  let not_retained = RetainedWindowLike::new(Box::new(DrawCounter { draws: draws.clone() }), false);
+  not_retained.set_surface([100, 100], vec![1, 2, 3]);
+  assert!(not_retained.surface([100, 100]).is_none());
+  //the surface isn't drawn by the retained window-like itself
+  assert!(*draws.borrow() == 0);
+}
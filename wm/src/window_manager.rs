@@ -4,19 +4,24 @@ use std::collections::{ HashMap, VecDeque };
 use std::fmt;
 use std::boxed::Box;
 use std::cell::RefCell;
+use std::rc::Rc;
 use std::fs::File;
 use std::io::Read;
 use std::str::FromStr;
 
 use linux::fb::Framebuffer;
-use ming_wm_lib::framebuffer_types::{ Point, Dimensions };
-use ming_wm_lib::themes::{ Themes, get_theme_info };
+use ming_wm_lib::framebuffer_types::{ Point, Dimensions, Rect };
+use ming_wm_lib::themes::{ Themes, ThemeInfo, get_theme_info };
 use ming_wm_lib::utils::{ min, point_inside, trunc_words };
 use ming_wm_lib::messages::*;
-use ming_wm_lib::dirs::config_dir;
+use ming_wm_lib::dirs::{ config_dir, exe_dir };
 use ming_wm_lib::window_manager_types::*;
-use crate::framebuffer::FramebufferWriter;
-use crate::proxy_window_like::ProxyWindowLike;
+use crate::framebuffer::{ FramebufferWriter, Rotation };
+use crate::config::Config;
+use crate::menu::{ self, OpenMenu, MenuResult };
+use crate::clipboard::{ ClipboardHistory, ExternalClipboard };
+use crate::retained::RetainedWindowLike;
+use crate::proxy_window_like::{ ProxyWindowLike, ProxyProcess };
 use crate::essential::desktop_background::DesktopBackground;
 use crate::essential::taskbar::Taskbar;
 use crate::essential::lock_screen::LockScreen;
@@ -25,25 +30,68 @@ use crate::essential::start_menu::StartMenu;
 use crate::essential::about::About;
 use crate::essential::help::Help;
 use crate::essential::onscreen_keyboard::OnscreenKeyboard;
+use crate::essential::launcher::Launcher;
+use crate::essential::dialog::Dialog;
 
 //todo: a lot of the usize should be changed to u16
 
 const WINDOW_TOP_HEIGHT: usize = 26;
+const ICON_SIZE: usize = 16;
+//home row first, like vimium
+const HINT_LETTERS: &str = "asdfghjklqwertyuiopzxcvbnm";
+const HINT_SIZE: usize = 24;
+const OVERVIEW_PADDING: usize = 16;
+const OVERVIEW_LABEL_HEIGHT: usize = 16;
+const PICKER_LINE_HEIGHT: usize = 20;
+const PICKER_MAX_ROWS: usize = 10;
+//mostly arbitrary, used if windows don't have a min size hint. not including the window top
+const MIN_WINDOW_SIZE: Dimensions = [100, 100];
 
 #[derive(PartialEq)]
+#[allow(clippy::enum_variant_names)]
 enum Workspace {
-  All,
+  All, //non-window window-likes, and sticky windows
   Workspace(u8), //goes from 0-8
+  Scratchpad, //hidden scratchpad windows
+}
+
+//who the open dialog is for
+enum DialogFor {
+  Close(usize), //confirm closing this window
+  Window(usize), //this window asked for it, and gets the result
 }
 
 struct WindowLikeInfo {
   id: usize,
-  window_like: WindowBox,
+  window_like: RetainedWindowLike,
   top_left: Point,
   old_top_left: Point,
   dimensions: Dimensions,
+  old_dimensions: Dimensions, //to go back to after fullscreen
   workspace: Workspace,
   fullscreen: bool,
+  always_on_top: bool,
+  scratchpad: bool, //still true when shown, so it can be hidden again
+  size_hints: SizeHints, //cached, since it can be an ipc call
+  icon: Option<String>, //cached too, only for windows
+  menu: Vec<MenuItem>, //cached too, only for windows. if not empty, the menu bar is under the window top
+  title: String, //cached, and updated after the window responds to something. only for windows
+  process: Option<(Rc<ProxyProcess>, usize)>, //for windows that are child processes, and the window id in that process
+}
+
+impl WindowLikeInfo {
+  //height of the window top, and the menu bar if there is one. only for windows
+  fn top_height(&self) -> usize {
+    WINDOW_TOP_HEIGHT + if self.menu.is_empty() { 0 } else { menu::BAR_HEIGHT }
+  }
+
+  fn in_workspace(&self, workspace: u8) -> bool {
+    match self.workspace {
+      Workspace::Workspace(w) => w == workspace,
+      Workspace::All => true,
+      Workspace::Scratchpad => false,
+    }
+  }
 }
 
 impl fmt::Debug for WindowLikeInfo {
@@ -55,7 +103,7 @@ impl fmt::Debug for WindowLikeInfo {
 
 pub struct WindowManager {
   writer: RefCell<FramebufferWriter>,
-  rotate: bool,
+  rotation: Rotation,
   grayscale: bool,
   id_count: usize,
   window_infos: Vec<WindowLikeInfo>,
@@ -66,19 +114,32 @@ pub struct WindowManager {
   pub locked: bool,
   current_workspace: u8,
   framebuffer: Framebuffer,
-  clipboard: Option<String>,
+  clipboard: ClipboardHistory,
+  external_clipboard: ExternalClipboard,
+  clipboard_picker: Option<usize>, //selected entry, while the clipboard history picker is open
+  dialog_for: Option<DialogFor>, //only one dialog at a time
+  menu: Option<OpenMenu>, //menu bar of the focused window, if open
+  hints: Option<Vec<(char, usize)>>, //letter and window id, while in hint mode
+  overview: Option<u8>, //selected workspace, while the overview is open
+  damage: Option<(usize, Vec<Rect>)>, //window id and the regions it changed, for the next draw
   version: String,
   password_hash: [u8; 64],
+  config: Config,
 }
 
 //1 is up, 2 is down
 
 impl WindowManager {
-  pub fn new(writer: FramebufferWriter, framebuffer: Framebuffer, dimensions: Dimensions, rotate: bool, grayscale: bool, version: String, password_hash: [u8; 64]) -> Self {
+  //dimensions are in pixels, but everything else in the window manager (and what windows get) is in logical units
+  //(see `FramebufferWriter`), so they are divided by the scale
+  pub fn new(mut writer: FramebufferWriter, framebuffer: Framebuffer, dimensions: Dimensions, rotation: Rotation, grayscale: bool, version: String, password_hash: [u8; 64]) -> Self {
     //println!("bg: {}x{}", dimensions[0], dimensions[1] - TASKBAR_HEIGHT - INDICATOR_HEIGHT);
+    let config = Config::load();
+    writer.set_scale(config.scale);
+    let dimensions = [(dimensions[0] as f32 / config.scale) as usize, (dimensions[1] as f32 / config.scale) as usize];
     let mut wm = WindowManager {
       writer: RefCell::new(writer),
-      rotate,
+      rotation,
       grayscale,
       id_count: 0,
       window_infos: Vec::new(),
@@ -89,9 +150,17 @@ impl WindowManager {
       locked: false,
       current_workspace: 0,
       framebuffer,
-      clipboard: None,
+      clipboard: ClipboardHistory::new(config.clipboard_size, config.clipboard_persist),
+      external_clipboard: ExternalClipboard::new(config.clipboard_copy_command.clone(), config.clipboard_paste_command.clone()),
+      clipboard_picker: None,
+      dialog_for: None,
+      menu: None,
+      hints: None,
+      overview: None,
+      damage: None,
       version,
       password_hash,
+      config,
     };
     wm.lock();
     wm.change_theme();
@@ -101,28 +170,57 @@ impl WindowManager {
   pub fn add_window_like(&mut self, mut window_like: Box<dyn WindowLike>, top_left: Point, dimensions: Option<Dimensions>) {
     let subtype = window_like.subtype();
     let dimensions = dimensions.unwrap_or(window_like.ideal_dimensions(self.dimensions));
+    let menu = if subtype == WindowLikeType::Window { window_like.menu() } else { Vec::new() };
+    let top_height = WINDOW_TOP_HEIGHT + if menu.is_empty() { 0 } else { menu::BAR_HEIGHT };
+    let size_hints = if subtype == WindowLikeType::Window { window_like.size_hints() } else { Default::default() };
+    let dimensions = if subtype == WindowLikeType::Window {
+      //the hints apply from the start, not just once the window is resized
+      let max_dimensions = [self.dimensions[0], self.dimensions[1] - TASKBAR_HEIGHT - INDICATOR_HEIGHT - top_height];
+      size_hints.constrain(dimensions, MIN_WINDOW_SIZE, max_dimensions)
+    } else {
+      dimensions
+    };
     self.id_count += 1;
     let id = self.id_count;
     window_like.handle_message(WindowMessage::Init(dimensions));
-    let dimensions = if subtype == WindowLikeType::Window { [dimensions[0], dimensions[1] + WINDOW_TOP_HEIGHT] } else { dimensions };
+    let dimensions = if subtype == WindowLikeType::Window { [dimensions[0], dimensions[1] + top_height] } else { dimensions };
+    let title = if subtype == WindowLikeType::Window { window_like.title() } else { String::new() };
+    let icon = if subtype == WindowLikeType::Window {
+      //generic icon for windows without one
+      Some(window_like.icon().unwrap_or(exe_dir(Some("ming_bmps/icons/window.bmp")).to_string_lossy().to_string()))
+    } else {
+      None
+    };
     let window_info = WindowLikeInfo {
       id,
-      window_like,
+      //the workspace indicator has a clock, which changes without it getting any messages
+      window_like: RetainedWindowLike::new(window_like, subtype != WindowLikeType::WorkspaceIndicator),
       top_left,
       old_top_left: top_left,
       dimensions,
+      old_dimensions: dimensions,
       workspace: if subtype == WindowLikeType::Window {
         Workspace::Workspace(self.current_workspace)
       } else {
         Workspace::All
       },
       fullscreen: false,
+      always_on_top: false,
+      scratchpad: false,
+      size_hints,
+      icon,
+      menu,
+      title,
+      process: None,
     };
     if subtype == WindowLikeType::OnscreenKeyboard {
       self.osk = Some(window_info);
     } else {
       self.focused_id = id;
       self.window_infos.push(window_info);
+      if subtype == WindowLikeType::Window {
+        self.raise_always_on_top();
+      }
     }
   }
 
@@ -135,13 +233,20 @@ impl WindowManager {
     self.window_infos.iter().filter(|w| {
       match w.workspace {
         Workspace::Workspace(workspace) => workspace == self.current_workspace,
-        _ => include_non_window, //filter out taskbar, indicator, background, start menu, etc if true
+        //filter out taskbar, indicator, background, start menu, etc if true, but not sticky windows
+        Workspace::All => include_non_window || w.window_like.subtype() == WindowLikeType::Window,
+        Workspace::Scratchpad => false,
       }
     }).collect()
   }
 
   fn lock(&mut self) {
     self.locked = true;
+    for entry in self.clipboard.entries.iter().filter(|e| e.sensitive) {
+      self.external_clipboard.forget(&entry.content);
+    }
+    self.clipboard.clear_sensitive();
+    self.clipboard_picker = None;
     self.window_infos = Vec::new();
     self.add_window_like(Box::new(LockScreen::new(self.password_hash)), [0, 0], None);
   }
@@ -164,6 +269,10 @@ impl WindowManager {
         self.theme = Themes::from_str(lines[self.current_workspace as usize]).unwrap_or_default();
       }
     }
+    //everything was drawn with the old theme
+    for window_info in self.window_infos.iter().chain(self.osk.iter()) {
+      window_info.window_like.invalidate();
+    }
   }
 
   //if off_only is true, also handle request
@@ -183,9 +292,382 @@ impl WindowManager {
     }
   }
 
+  //returns whether the launcher was open
+  fn close_launcher(&mut self) -> bool {
+    if let Some(launcher_index) = self.window_infos.iter().position(|w| w.window_like.subtype() == WindowLikeType::Launcher) {
+      let launcher = self.window_infos.remove(launcher_index);
+      if self.focused_id == launcher.id {
+        self.focus_top_window();
+      }
+      true
+    } else {
+      false
+    }
+  }
+
+  //returns the launcher id if it was opened
+  fn toggle_launcher(&mut self) -> Option<usize> {
+    if self.close_launcher() {
+      None
+    } else {
+      self.toggle_start_menu(true);
+      let launcher = Box::new(Launcher::new());
+      let ideal_dimensions = launcher.ideal_dimensions(self.dimensions);
+      self.add_window_like(launcher, [self.dimensions[0] / 2 - ideal_dimensions[0] / 2, INDICATOR_HEIGHT * 3], Some(ideal_dimensions));
+      Some(self.id_count)
+    }
+  }
+
+  fn close_window(&mut self, id: usize) {
+    if let Some(index) = self.window_infos.iter().position(|w| w.id == id) {
+      self.window_infos.remove(index);
+      if self.focused_id == id {
+        self.focus_top_window();
+      }
+      self.taskbar_update_windows();
+    }
+  }
+
+  //centred over the window it is for
+  fn open_dialog(&mut self, dialog_type: DialogType, dialog_for: DialogFor) {
+    self.toggle_start_menu(true);
+    self.close_launcher();
+    let owner_id = match dialog_for {
+      DialogFor::Close(id) | DialogFor::Window(id) => id,
+    };
+    let dialog = Dialog::new(dialog_type);
+    let ideal_dimensions = dialog.ideal_dimensions(self.dimensions);
+    let (owner_top_left, owner_dimensions) = if let Some(owner) = self.window_infos.iter().find(|w| w.id == owner_id) {
+      (owner.top_left, owner.dimensions)
+    } else {
+      ([0, 0], self.dimensions)
+    };
+    let centre = [owner_top_left[0] + owner_dimensions[0] / 2, owner_top_left[1] + owner_dimensions[1] / 2];
+    //keep it on screen
+    let top_left = [
+      min(centre[0].saturating_sub(ideal_dimensions[0] / 2), self.dimensions[0].saturating_sub(ideal_dimensions[0])),
+      min(centre[1].saturating_sub(ideal_dimensions[1] / 2), self.dimensions[1].saturating_sub(ideal_dimensions[1])),
+    ];
+    self.dialog_for = Some(dialog_for);
+    self.add_window_like(Box::new(dialog), top_left, Some(ideal_dimensions));
+  }
+
+  fn close_dialog(&mut self, result: DialogResult) {
+    if let Some(dialog_index) = self.window_infos.iter().position(|w| w.window_like.subtype() == WindowLikeType::Dialog) {
+      self.window_infos.remove(dialog_index);
+      match self.dialog_for.take() {
+        Some(DialogFor::Close(id)) => {
+          if result == DialogResult::Ok {
+            self.close_window(id);
+          } else {
+            self.focused_id = id;
+          }
+        },
+        Some(DialogFor::Window(id)) => {
+          self.focused_id = id;
+          if let Some(index) = self.get_focused_index() {
+            //the window may want to do something else, like open another dialog
+            if let WindowMessageResponse::Request(request) = self.window_infos[index].window_like.handle_message(WindowMessage::DialogResult(result)) {
+              self.handle_request(request);
+            }
+          }
+        },
+        None => {},
+      }
+      if self.get_focused_index().is_none() {
+        self.focus_top_window();
+      }
+    }
+  }
+
+  //returns whether the workspace changed
+  fn switch_workspace(&mut self, workspace: u8) -> bool {
+    if self.current_workspace == workspace {
+      return false;
+    }
+    //close start menu and launcher if open
+    self.toggle_start_menu(true);
+    self.close_launcher();
+    self.current_workspace = workspace;
+    //change theme
+    self.change_theme();
+    //send to desktop background
+    let desktop_background_index = self.window_infos.iter().position(|w| w.window_like.subtype() == WindowLikeType::DesktopBackground).unwrap();
+    self.window_infos[desktop_background_index].window_like.handle_message(WindowMessage::Shortcut(ShortcutType::SwitchWorkspace(self.current_workspace)));
+    //send to workspace indicator
+    let indicator_index = self.window_infos.iter().position(|w| w.window_like.subtype() == WindowLikeType::WorkspaceIndicator).unwrap();
+    self.focused_id = self.window_infos[indicator_index].id;
+    self.window_infos[indicator_index].window_like.handle_message(WindowMessage::Shortcut(ShortcutType::SwitchWorkspace(self.current_workspace)));
+    self.taskbar_update_windows();
+    true
+  }
+
+  //returns whether the focused window was moved
+  fn move_focused_to_workspace(&mut self, workspace: u8) -> bool {
+    if self.current_workspace != workspace {
+      if let Some(focused_index) = self.get_focused_index() {
+        if self.window_infos[focused_index].window_like.subtype() == WindowLikeType::Window {
+          self.window_infos[focused_index].workspace = Workspace::Workspace(workspace);
+          //it isn't shown anymore, so shouldn't get keys
+          self.focus_top_window();
+          self.taskbar_update_windows();
+          return true;
+        }
+      }
+    }
+    false
+  }
+
+  //hjkl to select a workspace, enter to switch to it, m to move the focused window there, esc to close
+  fn overview_key_press(&mut self, key_char: KeyChar) -> WindowMessageResponse {
+    let selected = self.overview.unwrap();
+    let (column, row) = (selected % 3, selected / 3);
+    if let KeyChar::Press(c) = key_char {
+      let key_press = KeyPress { key: c };
+      if c == 'h' || key_press.is_left_arrow() {
+        self.overview = Some(row * 3 + (column + 2) % 3);
+      } else if c == 'l' || key_press.is_right_arrow() {
+        self.overview = Some(row * 3 + (column + 1) % 3);
+      } else if c == 'k' || key_press.is_up_arrow() {
+        self.overview = Some((row + 2) % 3 * 3 + column);
+      } else if c == 'j' || key_press.is_down_arrow() {
+        self.overview = Some((row + 1) % 3 * 3 + column);
+      } else if ('1'..='9').contains(&c) {
+        self.overview = Some(c as u8 - b'1');
+      } else if key_press.is_enter() {
+        self.overview = None;
+        self.switch_workspace(selected);
+      } else if c == 'm' {
+        self.move_focused_to_workspace(selected);
+      } else if key_press.is_escape() {
+        self.overview = None;
+      } else {
+        return WindowMessageResponse::DoNothing;
+      }
+      WindowMessageResponse::JustRedraw
+    } else {
+      WindowMessageResponse::DoNothing
+    }
+  }
+
+  fn paste_latest(&mut self) -> WindowMessageResponse {
+    //something copied outside of ming (eg, with ming-clip) since the last copy is newer
+    if let Some(content) = self.external_clipboard.read_new() {
+      self.clipboard.copy(content);
+    }
+    if let Some(focused_index) = self.get_focused_index() {
+      let window_like = &self.window_infos[focused_index].window_like;
+      if window_like.subtype() == WindowLikeType::Window {
+        if let Some(content) = self.clipboard.latest() {
+          return self.window_infos[focused_index].window_like.handle_message(WindowMessage::Shortcut(ShortcutType::ClipboardPaste(content.clone())));
+        }
+      }
+    }
+    WindowMessageResponse::DoNothing
+  }
+
+  //jk to select, enter to paste, p to pin, s to mark sensitive, d to delete, esc to close
+  fn clipboard_picker_key_press(&mut self, key_char: KeyChar) -> WindowMessageResponse {
+    let selected = self.clipboard_picker.unwrap();
+    let length = self.clipboard.entries.len();
+    if let KeyChar::Press(c) = key_char {
+      let key_press = KeyPress { key: c };
+      if c == 'j' || key_press.is_down_arrow() {
+        self.clipboard_picker = Some((selected + 1) % length);
+      } else if c == 'k' || key_press.is_up_arrow() {
+        self.clipboard_picker = Some((selected + length - 1) % length);
+      } else if key_press.is_enter() {
+        self.clipboard_picker = None;
+        self.clipboard.promote(selected);
+        //so it is not replaced by the older external content when pasting. sensitive entries stay inside ming
+        if !self.clipboard.entries[0].sensitive {
+          let content = self.clipboard.entries[0].content.clone();
+          self.external_clipboard.write(&content);
+        }
+        //the picker is removed either way, so the response is not needed
+        self.paste_latest();
+      } else if c == 'p' {
+        self.clipboard.toggle_pinned(selected);
+      } else if c == 's' {
+        self.clipboard.toggle_sensitive(selected);
+        if self.clipboard.entries[selected].sensitive {
+          self.external_clipboard.forget(&self.clipboard.entries[selected].content);
+        }
+      } else if c == 'd' {
+        self.clipboard.remove(selected);
+        self.clipboard_picker = if length == 1 { None } else { Some(selected.min(length - 2)) };
+      } else if key_press.is_escape() {
+        self.clipboard_picker = None;
+      } else {
+        return WindowMessageResponse::DoNothing;
+      }
+      WindowMessageResponse::JustRedraw
+    } else {
+      WindowMessageResponse::DoNothing
+    }
+  }
+
+  //lowercase letter focuses the window, uppercase also swaps it with the focused window. anything else cancels
+  fn hint_key_press(&mut self, key_char: KeyChar) -> WindowMessageResponse {
+    let hints = self.hints.take().unwrap();
+    if let KeyChar::Press(c) = key_char {
+      if let Some(&(_, id)) = hints.iter().find(|h| h.0 == c.to_ascii_lowercase()) {
+        if let Some(index) = self.window_infos.iter().position(|w| w.id == id) {
+          if c.is_ascii_uppercase() {
+            if let Some(focused_index) = self.get_focused_index() {
+              let focused_info = &self.window_infos[focused_index];
+              if focused_index != index && focused_info.window_like.subtype() == WindowLikeType::Window && !focused_info.fullscreen && !self.window_infos[index].fullscreen {
+                let focused_top_left = focused_info.top_left;
+                self.window_infos[focused_index].top_left = self.window_infos[index].top_left;
+                self.window_infos[index].top_left = focused_top_left;
+              }
+            }
+          }
+          self.focused_id = id;
+          self.move_index_to_top(index);
+          self.taskbar_update_windows();
+        }
+      }
+    }
+    WindowMessageResponse::JustRedraw
+  }
+
+  //the menu is for the focused window
+  fn menu_key_press(&mut self, key_char: KeyChar) -> WindowMessageResponse {
+    let menu = self.menu.as_mut().unwrap();
+    let result = if let KeyChar::Press(c) = key_char {
+      menu.handle_key(KeyPress { key: c })
+    } else {
+      MenuResult::Nothing
+    };
+    match result {
+      MenuResult::Nothing => WindowMessageResponse::DoNothing,
+      MenuResult::Redraw => WindowMessageResponse::JustRedraw,
+      MenuResult::Close => {
+        self.menu = None;
+        WindowMessageResponse::JustRedraw
+      },
+      MenuResult::Action(action) => {
+        self.menu = None;
+        let focused_index = self.get_focused_index().unwrap();
+        match self.window_infos[focused_index].window_like.handle_message(WindowMessage::MenuAction(action)) {
+          //menu needs to be undrawn regardless
+          WindowMessageResponse::DoNothing => WindowMessageResponse::JustRedraw,
+          response => response,
+        }
+      },
+    }
+  }
+
+  fn set_process(&mut self, id: usize, process: Rc<ProxyProcess>, window_id: usize) {
+    if let Some(window_info) = self.window_infos.iter_mut().find(|w| w.id == id) {
+      window_info.process = Some((process, window_id));
+    }
+  }
+
+  //whether other windows are from the same process, and so share state
+  fn shares_process(&self, index: usize) -> bool {
+    if let Some((process, _)) = &self.window_infos[index].process {
+      Rc::strong_count(process) > 2 //one from the proxy, one from the window info
+    } else {
+      false
+    }
+  }
+
+  //focus the top most window in the current workspace, if there is one
+  //if there are no windows left, the workspace indicator gets focus, same as after switching to an empty workspace
+  fn focus_top_window(&mut self) {
+    if let Some(index) = self.window_infos.iter().rposition(|w| w.window_like.subtype() == WindowLikeType::Window && w.in_workspace(self.current_workspace)) {
+      self.focused_id = self.window_infos[index].id;
+    } else if let Some(index) = self.window_infos.iter().position(|w| w.window_like.subtype() == WindowLikeType::WorkspaceIndicator) {
+      self.focused_id = self.window_infos[index].id;
+    }
+  }
+
+  fn center_index(&mut self, index: usize) {
+    let window_dimensions = &self.window_infos[index].dimensions;
+    self.window_infos[index].top_left = [self.dimensions[0] / 2 - window_dimensions[0] / 2, self.dimensions[1] / 2 - window_dimensions[1] / 2];
+  }
+
+  //hide the top most shown scratchpad window in the current workspace, or show the top most hidden one
+  fn toggle_scratchpad(&mut self) -> bool {
+    if let Some(index) = self.window_infos.iter().rposition(|w| w.scratchpad && w.in_workspace(self.current_workspace)) {
+      self.window_infos[index].workspace = Workspace::Scratchpad;
+      self.focus_top_window();
+      true
+    } else if let Some(index) = self.window_infos.iter().rposition(|w| w.scratchpad && w.workspace != Workspace::Scratchpad) {
+      //shown in another workspace, so bring it here
+      self.show_scratchpad_index(index);
+      true
+    } else if let Some(index) = self.window_infos.iter().rposition(|w| w.workspace == Workspace::Scratchpad) {
+      self.show_scratchpad_index(index);
+      true
+    } else {
+      false
+    }
+  }
+
+  fn show_scratchpad_index(&mut self, index: usize) {
+    self.window_infos[index].workspace = Workspace::Workspace(self.current_workspace);
+    if !self.window_infos[index].fullscreen {
+      self.center_index(index);
+    }
+    self.focused_id = self.window_infos[index].id;
+    self.move_index_to_top(index);
+  }
+
+  //if the window is already snapped to one of the rects, go to the next one, otherwise go to the closest one
+  //the window may end up smaller than the rect because of its size hints
+  //returns whether the window was snapped
+  fn snap_index(&mut self, index: usize, rects: Vec<(Point, Dimensions)>) -> bool {
+    let window_like = &self.window_infos[index].window_like;
+    if window_like.subtype() != WindowLikeType::Window || !window_like.resizable() || rects.len() == 0 {
+      return false;
+    }
+    let window_info = &mut self.window_infos[index];
+    let next_index = if let Some(current_index) = rects.iter().position(|r| r.0 == window_info.top_left) {
+      (current_index + 1) % rects.len()
+    } else {
+      rects.iter().enumerate().min_by_key(|(_, r)| r.0[0].abs_diff(window_info.top_left[0]) + r.0[1].abs_diff(window_info.top_left[1])).unwrap().0
+    };
+    window_info.fullscreen = false;
+    let (top_left, max_dimensions) = rects[next_index];
+    let max_dimensions = [max_dimensions[0], max_dimensions[1] - window_info.top_height()];
+    let new_dimensions = window_info.size_hints.constrain(max_dimensions, MIN_WINDOW_SIZE, max_dimensions);
+    window_info.top_left = top_left;
+    window_info.dimensions = [new_dimensions[0], new_dimensions[1] + window_info.top_height()];
+    window_info.window_like.handle_message(WindowMessage::ChangeDimensions(new_dimensions));
+    true
+  }
+
+  //windows can change their title whenever they respond to something (eg, the file open in an editor),
+  //returns whether any titles changed, in which case the taskbar was updated
+  fn refresh_titles(&mut self, index: usize) -> bool {
+    let mut changed = false;
+    for i in 0..self.window_infos.len() {
+      //windows of the same process may have changed too
+      let same_process = match (&self.window_infos[i].process, &self.window_infos[index].process) {
+        (Some((p1, _)), Some((p2, _))) => Rc::ptr_eq(p1, p2),
+        _ => false,
+      };
+      if (i == index || same_process) && self.window_infos[i].window_like.subtype() == WindowLikeType::Window {
+        let title = self.window_infos[i].window_like.title();
+        if title != self.window_infos[i].title {
+          self.window_infos[i].title = title;
+          changed = true;
+        }
+      }
+    }
+    if changed {
+      self.taskbar_update_windows();
+    }
+    changed
+  }
+
+  #[allow(clippy::unnecessary_sort_by)]
   fn taskbar_update_windows(&mut self) {
     let taskbar_index = self.window_infos.iter().position(|w| w.window_like.subtype() == WindowLikeType::Taskbar).unwrap();
-    let mut relevant: WindowsVec = self.get_windows_in_workspace(false).iter().map(|w| (w.id, w.window_like.title().to_string())).collect();
+    let mut relevant: WindowsVec = self.get_windows_in_workspace(false).iter().map(|w| (w.id, w.title.clone(), w.icon.clone().unwrap_or_default())).collect();
     relevant.sort_by(|a, b| a.0.cmp(&b.0)); //sort by ids so order is consistent
     let message = WindowMessage::Info(InfoType::WindowsInWorkspace(
       relevant,
@@ -197,11 +679,23 @@ impl WindowManager {
   fn move_index_to_top(&mut self, index: usize) {
     let removed = self.window_infos.remove(index);
     self.window_infos.push(removed);
+    self.raise_always_on_top();
   }
 
+  //keep always on top windows above all other windows, in the same order
+  fn raise_always_on_top(&mut self) {
+    let (on_top, mut rest): (Vec<WindowLikeInfo>, Vec<WindowLikeInfo>) = self.window_infos.drain(..).partition(|w| w.always_on_top);
+    rest.extend(on_top);
+    self.window_infos = rest;
+  }
+
+  #[allow(clippy::unnecessary_unwrap, clippy::single_match)]
   pub fn handle_message(&mut self, message: WindowManagerMessage) {
-    let mut use_saved_buffer = false;
     let mut redraw_ids = None;
+    //the menu bar closes when the window it is for is no longer focused
+    if self.menu.as_ref().is_some_and(|m| m.id != self.focused_id) {
+      self.menu = None;
+    }
     let response: WindowMessageResponse = match message {
       WindowManagerMessage::KeyChar(key_char) => {
         //check if is special key (key releases are guaranteed to be special keys)
@@ -214,14 +708,29 @@ impl WindowManager {
               let shortcuts = HashMap::from([
                 //alt+E kills ming-wm when it is unlocked, but that is handled at a higher level
                 ('s', ShortcutType::StartMenu),
+                ('d', ShortcutType::Launcher),
                 ('[', ShortcutType::FocusPrevWindow),
                 (']', ShortcutType::FocusNextWindow),
                 ('q', ShortcutType::QuitWindow),
+                ('Q', ShortcutType::ForceQuitWindow),
                 ('c', ShortcutType::CenterWindow),
                 ('f', ShortcutType::FullscreenWindow),
                 ('w', ShortcutType::HalfWidthWindow),
+                ('a', ShortcutType::QuarterWindow),
+                ('t', ShortcutType::ThirdWidthWindow),
+                ('W', ShortcutType::TwoThirdsWidthWindow),
+                ('y', ShortcutType::MaximizeWindowVertically),
+                ('x', ShortcutType::MaximizeWindowHorizontally),
+                ('S', ShortcutType::ToggleSticky),
+                ('o', ShortcutType::ToggleAlwaysOnTop),
+                ('-', ShortcutType::ToggleScratchpad),
+                ('_', ShortcutType::MoveWindowToScratchpad),
+                (' ', ShortcutType::OpenMenu),
+                ('g', ShortcutType::WindowHints),
+                ('z', ShortcutType::Overview),
                 ('C', ShortcutType::ClipboardCopy),
                 ('P', ShortcutType::ClipboardPaste(String::new())),
+                ('V', ShortcutType::ClipboardHistory),
                 //move window a small amount
                 ('h', ShortcutType::MoveWindow(Direction::Left)),
                 ('j', ShortcutType::MoveWindow(Direction::Down)),
@@ -238,6 +747,11 @@ impl WindowManager {
                 //shrink window size
                 ('N', ShortcutType::ChangeWindowSize(Direction::Left)),
                 ('M', ShortcutType::ChangeWindowSize(Direction::Up)),
+                //expand and shrink from the left and top edges
+                (',', ShortcutType::ChangeWindowSizeLeftTop(Direction::Left)),
+                ('.', ShortcutType::ChangeWindowSizeLeftTop(Direction::Up)),
+                ('<', ShortcutType::ChangeWindowSizeLeftTop(Direction::Right)),
+                ('>', ShortcutType::ChangeWindowSizeLeftTop(Direction::Down)),
                 //no 10th workspace
                 ('1', ShortcutType::SwitchWorkspace(0)),
                 ('2', ShortcutType::SwitchWorkspace(1)),
@@ -260,7 +774,22 @@ impl WindowManager {
                 ('(', ShortcutType::MoveWindowToWorkspace(8)),
                 //
               ]);
-              if let Some(shortcut) = shortcuts.get(&c) {
+              //keyboard focus is locked to the dialog if there is one, but it can be force quit or pasted into
+              let dialog_open = self.dialog_for.is_some();
+              if let Some(shortcut) = shortcuts.get(&c).filter(|s| !dialog_open || matches!(s, ShortcutType::ForceQuitWindow | ShortcutType::ClipboardPaste(_) | ShortcutType::ClipboardHistory)) {
+                //any other shortcut closes the menu bar or leaves hint mode
+                if shortcut != &ShortcutType::OpenMenu && self.menu.take().is_some() {
+                  press_response = WindowMessageResponse::JustRedraw;
+                }
+                if shortcut != &ShortcutType::WindowHints && self.hints.take().is_some() {
+                  press_response = WindowMessageResponse::JustRedraw;
+                }
+                if shortcut != &ShortcutType::Overview && self.overview.take().is_some() {
+                  press_response = WindowMessageResponse::JustRedraw;
+                }
+                if shortcut != &ShortcutType::ClipboardHistory && self.clipboard_picker.take().is_some() {
+                  press_response = WindowMessageResponse::JustRedraw;
+                }
                 match shortcut {
                   &ShortcutType::StartMenu => {
                     //send to taskbar
@@ -272,6 +801,13 @@ impl WindowManager {
                       redraw_ids = Some(vec![start_menu_id, taskbar_id]);
                     }
                   },
+                  &ShortcutType::Launcher => {
+                    press_response = WindowMessageResponse::JustRedraw;
+                    if let Some(launcher_id) = self.toggle_launcher() {
+                      //only thing that needs to be drawn is the launcher itself
+                      redraw_ids = Some(vec![launcher_id]);
+                    }
+                  },
                   &ShortcutType::MoveWindow(direction) | &ShortcutType::MoveWindowToEdge(direction) => {
                     if let Some(focused_index) = self.get_focused_index() {
                       let focused_info = &self.window_infos[focused_index];
@@ -318,102 +854,73 @@ impl WindowManager {
                         }
                         if changed {
                           press_response = WindowMessageResponse::JustRedraw;
-                          //avoid drawing everything under the moving window, much more efficient
-                          use_saved_buffer = true;
-                          redraw_ids = Some(vec![self.focused_id]);
                         }
                       }
                     }
                   },
-                  &ShortcutType::ChangeWindowSize(direction) => {
+                  &ShortcutType::ChangeWindowSize(direction) | &ShortcutType::ChangeWindowSizeLeftTop(direction) => {
                     if let Some(focused_index) = self.get_focused_index() {
                       let focused_info = &self.window_infos[focused_index];
                       if focused_info.window_like.subtype() == WindowLikeType::Window && focused_info.window_like.resizable() && !focused_info.fullscreen {
-                        //mostly arbitrary
-                        let min_window_size = [100, WINDOW_TOP_HEIGHT + 100];
-                        let mut changed = false;
-                        let delta = 15;
-                        let window = &mut self.window_infos[focused_index];
-                        if direction == Direction::Right {
-                          //expand x
-                          if window.dimensions[0] + delta != self.dimensions[0] {
-                            window.dimensions[0] += delta;
-                            let max_width = self.dimensions[0] - window.top_left[0];
-                            if window.dimensions[0] > max_width {
-                              window.dimensions[0] = max_width;
-                            }
-                            changed = true;
-                          }
-                        } else if direction == Direction::Down {
-                          //expand y
-                          let max_height = self.dimensions[1] - window.top_left[1] - INDICATOR_HEIGHT - TASKBAR_HEIGHT;
-                          if window.dimensions[1] + delta != max_height {
-                            window.dimensions[1] += delta;
-                            if window.dimensions[1] > max_height {
-                              window.dimensions[1] = max_height;
-                            }
-                            changed = true;
-                          }
-                        } else if direction == Direction::Left {
-                          //shrink x
-                          if window.dimensions[0] - delta != min_window_size[0] {
-                            window.dimensions[0] -= delta;
-                            if window.dimensions[0] < min_window_size[0] {
-                              window.dimensions[0] = min_window_size[0];
-                            }
-                            changed = true;
-                          }
-                        } else if direction == Direction::Up {
-                          //shrink y
-                          if window.dimensions[1] - delta != min_window_size[1] {
-                            window.dimensions[1] -= delta;
-                            if window.dimensions[1] < min_window_size[1] {
-                              window.dimensions[1] = min_window_size[1];
-                            }
-                            changed = true;
-                          }
+                        //window content dimensions, not including the window top
+                        let top_left = focused_info.top_left;
+                        let top_height = focused_info.top_height();
+                        let dimensions = [focused_info.dimensions[0], focused_info.dimensions[1] - top_height];
+                        let size_hints = &focused_info.size_hints;
+                        //change by at least one increment, or it would just get rounded back down
+                        let increment = size_hints.increment.unwrap_or([1, 1]);
+                        let delta = [self.config.resize_step.max(increment[0]), self.config.resize_step.max(increment[1])];
+                        let left_top = matches!(shortcut, &ShortcutType::ChangeWindowSizeLeftTop(_));
+                        let max_dimensions = if left_top {
+                          //the right and bottom edges stay in place, and don't go over the workspace indicator
+                          [top_left[0] + dimensions[0], (top_left[1] + dimensions[1]).saturating_sub(INDICATOR_HEIGHT)]
+                        } else {
+                          [self.dimensions[0].saturating_sub(top_left[0]), self.dimensions[1].saturating_sub(top_left[1] + TASKBAR_HEIGHT + top_height)]
+                        };
+                        //whether to expand, and which axis
+                        let (expand, axis) = match (direction, left_top) {
+                          (Direction::Right, false) | (Direction::Left, true) => (true, 0),
+                          (Direction::Down, false) | (Direction::Up, true) => (true, 1),
+                          (Direction::Left, false) | (Direction::Right, true) => (false, 0),
+                          (Direction::Up, false) | (Direction::Down, true) => (false, 1),
+                        };
+                        let mut new_dimensions = dimensions;
+                        new_dimensions[axis] = if expand {
+                          dimensions[axis] + delta[axis]
+                        } else {
+                          dimensions[axis].saturating_sub(delta[axis])
+                        };
+                        if let Some(aspect_ratio) = size_hints.aspect_ratio {
+                          //change the other axis too, to keep the ratio
+                          let other = 1 - axis;
+                          new_dimensions[other] = new_dimensions[axis] * aspect_ratio[other] / aspect_ratio[axis].max(1);
                         }
-                        if changed {
-                          let new_dimensions = [window.dimensions[0], window.dimensions[1] - WINDOW_TOP_HEIGHT];
-                          self.window_infos[focused_index].window_like.handle_message(WindowMessage::ChangeDimensions(new_dimensions));
+                        let new_dimensions = size_hints.constrain(new_dimensions, MIN_WINDOW_SIZE, max_dimensions);
+                        if new_dimensions != dimensions {
+                          let window = &mut self.window_infos[focused_index];
+                          if left_top {
+                            window.top_left = [top_left[0] + dimensions[0] - new_dimensions[0], top_left[1] + dimensions[1] - new_dimensions[1]];
+                          }
+                          window.dimensions = [new_dimensions[0], new_dimensions[1] + top_height];
+                          window.window_like.handle_message(WindowMessage::ChangeDimensions(new_dimensions));
                           press_response = WindowMessageResponse::JustRedraw;
-                          use_saved_buffer = true;
-                          redraw_ids = Some(vec![self.focused_id]);
                         }
                       }
                     }
                   },
                   &ShortcutType::SwitchWorkspace(workspace) => {
-                    if self.current_workspace != workspace {
-                      //close start menu if open
-                      self.toggle_start_menu(true);
-                      self.current_workspace = workspace;
-                      //change theme
-                      self.change_theme();
-                      //send to desktop background
-                      let desktop_background_index = self.window_infos.iter().position(|w| w.window_like.subtype() == WindowLikeType::DesktopBackground).unwrap();
-                      self.window_infos[desktop_background_index].window_like.handle_message(WindowMessage::Shortcut(ShortcutType::SwitchWorkspace(self.current_workspace)));
-                      //send to workspace indicator
-                      let indicator_index = self.window_infos.iter().position(|w| w.window_like.subtype() == WindowLikeType::WorkspaceIndicator).unwrap();
-                      self.focused_id = self.window_infos[indicator_index].id;
-                      self.window_infos[indicator_index].window_like.handle_message(WindowMessage::Shortcut(ShortcutType::SwitchWorkspace(self.current_workspace)));
-                      self.taskbar_update_windows();
+                    if self.switch_workspace(workspace) {
                       press_response = WindowMessageResponse::JustRedraw;
                     }
                   },
                   &ShortcutType::MoveWindowToWorkspace(workspace) => {
-                    if self.current_workspace != workspace {
-                      if let Some(focused_index) = self.get_focused_index() {
-                        if self.window_infos[focused_index].window_like.subtype() == WindowLikeType::Window {
-                          self.window_infos[focused_index].workspace = Workspace::Workspace(workspace);
-                          self.taskbar_update_windows();
-                          press_response = WindowMessageResponse::JustRedraw;
-                        }
-                      }
+                    if self.move_focused_to_workspace(workspace) {
+                      press_response = WindowMessageResponse::JustRedraw;
                     }
                   },
                   &ShortcutType::FocusPrevWindow | &ShortcutType::FocusNextWindow => {
                     self.toggle_start_menu(true);
+                    self.close_launcher();
                     let current_index = self.get_focused_index().unwrap_or(0);
                     let mut new_focus_index = current_index;
                     loop {
@@ -429,7 +936,7 @@ impl WindowManager {
                           new_focus_index = 0;
                         }
                       }
-                      if self.window_infos[new_focus_index].window_like.subtype() == WindowLikeType::Window && self.window_infos[new_focus_index].workspace == Workspace::Workspace(self.current_workspace) {
+                      if self.window_infos[new_focus_index].window_like.subtype() == WindowLikeType::Window && self.window_infos[new_focus_index].in_workspace(self.current_workspace) {
                         //switch focus to this
                         self.focused_id = self.window_infos[new_focus_index].id;
                         //elevate it to the top
@@ -445,17 +952,48 @@ impl WindowManager {
                   &ShortcutType::QuitWindow => {
                     if let Some(focused_index) = self.get_focused_index() {
                       if self.window_infos[focused_index].window_like.subtype() == WindowLikeType::Window {
-                        self.window_infos.remove(focused_index);
-                        self.taskbar_update_windows();
+                        //the window can say no, or that the user should be asked first
+                        match self.window_infos[focused_index].window_like.handle_message(WindowMessage::CloseRequest) {
+                          WindowMessageResponse::Request(WindowManagerRequest::DenyClose) => {
+                            //it may have changed to show why
+                            press_response = WindowMessageResponse::JustRedraw;
+                          },
+                          WindowMessageResponse::Request(WindowManagerRequest::ConfirmClose(message)) => {
+                            self.open_dialog(DialogType::Confirm(message), DialogFor::Close(self.focused_id));
+                            press_response = WindowMessageResponse::JustRedraw;
+                          },
+                          _ => {
+                            self.close_window(self.focused_id);
+                            press_response = WindowMessageResponse::JustRedraw;
+                          },
+                        };
+                      }
+                    }
+                  },
+                  &ShortcutType::ForceQuitWindow => {
+                    if let Some(focused_index) = self.get_focused_index() {
+                      let subtype = self.window_infos[focused_index].window_like.subtype();
+                      if subtype == WindowLikeType::Window {
+                        self.close_window(self.focused_id);
+                        press_response = WindowMessageResponse::JustRedraw;
+                      } else if subtype == WindowLikeType::Dialog {
+                        //force quit the window the dialog is for
+                        let owner_id = match self.dialog_for {
+                          Some(DialogFor::Close(id)) | Some(DialogFor::Window(id)) => Some(id),
+                          None => None,
+                        };
+                        self.dialog_for = None;
+                        self.close_dialog(DialogResult::Cancelled);
+                        if let Some(owner_id) = owner_id {
+                          self.close_window(owner_id);
+                        }
                         press_response = WindowMessageResponse::JustRedraw;
                       }
                     }
                   },
                   &ShortcutType::CenterWindow => {
                     if let Some(focused_index) = self.get_focused_index() {
-                      let window_dimensions = &self.window_infos[focused_index].dimensions;
-                      self.window_infos[focused_index].top_left = [self.dimensions[0] / 2 - window_dimensions[0] / 2, self.dimensions[1] / 2 - window_dimensions[1] / 2];
-                      use_saved_buffer = true;
+                      self.center_index(focused_index);
                       press_response = WindowMessageResponse::JustRedraw;
                     }
                   },
@@ -467,17 +1005,21 @@ impl WindowManager {
                         let window_info = &mut self.window_infos[focused_index];
                         window_info.fullscreen ^= true;
                         //todo: send message to window about resize
-                        let new_dimensions;
                         if window_info.fullscreen {
-                          new_dimensions = [self.dimensions[0], self.dimensions[1] - TASKBAR_HEIGHT - INDICATOR_HEIGHT];
+                          //the window may not want to fill all of it
+                          let max_dimensions = [self.dimensions[0], self.dimensions[1] - TASKBAR_HEIGHT - INDICATOR_HEIGHT - window_info.top_height()];
+                          let content_dimensions = window_info.size_hints.constrain(max_dimensions, MIN_WINDOW_SIZE, max_dimensions);
                           window_info.old_top_left = window_info.top_left;
+                          window_info.old_dimensions = window_info.dimensions;
                           window_info.top_left = [0, INDICATOR_HEIGHT];
+                          window_info.dimensions = [content_dimensions[0], content_dimensions[1] + window_info.top_height()];
                           redraw_ids = Some(vec![window_info.id]);
                         } else {
                           window_info.top_left = window_info.old_top_left;
-                          new_dimensions = window_info.dimensions;
+                          window_info.dimensions = window_info.old_dimensions;
                         }
-                        window_info.window_like.handle_message(WindowMessage::ChangeDimensions([new_dimensions[0], new_dimensions[1] - WINDOW_TOP_HEIGHT]));
+                        let dimensions = window_info.dimensions;
+                        window_info.window_like.handle_message(WindowMessage::ChangeDimensions([dimensions[0], dimensions[1] - window_info.top_height()]));
                         press_response = WindowMessageResponse::JustRedraw;
                       }
                     }
@@ -495,9 +1037,129 @@ impl WindowManager {
                         }
                         top_left[1] = INDICATOR_HEIGHT;
                         //full height, half width
-                        let new_dimensions = [self.dimensions[0] / 2, self.dimensions[1] - INDICATOR_HEIGHT - TASKBAR_HEIGHT];
-                        self.window_infos[focused_index].dimensions = new_dimensions;
-                        self.window_infos[focused_index].window_like.handle_message(WindowMessage::ChangeDimensions([new_dimensions[0], new_dimensions[1] - WINDOW_TOP_HEIGHT]));
+                        let top_height = self.window_infos[focused_index].top_height();
+                        let max_dimensions = [self.dimensions[0] / 2, self.dimensions[1] - INDICATOR_HEIGHT - TASKBAR_HEIGHT - top_height];
+                        let new_dimensions = self.window_infos[focused_index].size_hints.constrain(max_dimensions, MIN_WINDOW_SIZE, max_dimensions);
+                        self.window_infos[focused_index].dimensions = [new_dimensions[0], new_dimensions[1] + top_height];
+                        self.window_infos[focused_index].window_like.handle_message(WindowMessage::ChangeDimensions(new_dimensions));
+                        press_response = WindowMessageResponse::JustRedraw;
+                      }
+                    }
+                  },
+                  &ShortcutType::QuarterWindow | &ShortcutType::ThirdWidthWindow | &ShortcutType::TwoThirdsWidthWindow | &ShortcutType::MaximizeWindowVertically | &ShortcutType::MaximizeWindowHorizontally => {
+                    if let Some(focused_index) = self.get_focused_index() {
+                      let window_info = &self.window_infos[focused_index];
+                      let (top, width, height) = (INDICATOR_HEIGHT, self.dimensions[0], self.dimensions[1] - INDICATOR_HEIGHT - TASKBAR_HEIGHT);
+                      let rects = match *shortcut {
+                        ShortcutType::QuarterWindow => {
+                          let size = [width / 2, height / 2];
+                          //clockwise from the top left
+                          vec![([0, top], size), ([width / 2, top], size), ([width / 2, top + height / 2], size), ([0, top + height / 2], size)]
+                        },
+                        ShortcutType::ThirdWidthWindow => {
+                          let size = [width / 3, height];
+                          vec![([0, top], size), ([width / 3, top], size), ([width / 3 * 2, top], size)]
+                        },
+                        ShortcutType::TwoThirdsWidthWindow => {
+                          let size = [width / 3 * 2, height];
+                          vec![([0, top], size), ([width - size[0], top], size)]
+                        },
+                        ShortcutType::MaximizeWindowVertically => {
+                          vec![([window_info.top_left[0], top], [window_info.dimensions[0], height])]
+                        },
+                        _ => {
+                          //MaximizeWindowHorizontally, keep it on screen
+                          let window_height = min(window_info.dimensions[1], height);
+                          let y = min(window_info.top_left[1].max(top), top + height - window_height);
+                          vec![([0, y], [width, window_height])]
+                        },
+                      };
+                      if self.snap_index(focused_index, rects) {
+                        press_response = WindowMessageResponse::JustRedraw;
+                      }
+                    }
+                  },
+                  &ShortcutType::ToggleSticky => {
+                    if let Some(focused_index) = self.get_focused_index() {
+                      if self.window_infos[focused_index].window_like.subtype() == WindowLikeType::Window {
+                        let window_info = &mut self.window_infos[focused_index];
+                        //sticky windows are in every workspace
+                        window_info.workspace = if window_info.workspace == Workspace::All {
+                          Workspace::Workspace(self.current_workspace)
+                        } else {
+                          Workspace::All
+                        };
+                        self.taskbar_update_windows();
+                        press_response = WindowMessageResponse::JustRedraw;
+                        //taskbar needs to be redrawn too
+                        let taskbar_id = self.window_infos.iter().find(|w| w.window_like.subtype() == WindowLikeType::Taskbar).unwrap().id;
+                        redraw_ids = Some(vec![self.focused_id, taskbar_id]);
+                      }
+                    }
+                  },
+                  &ShortcutType::OpenMenu => {
+                    if self.menu.take().is_some() {
+                      press_response = WindowMessageResponse::JustRedraw;
+                      redraw_ids = Some(vec![self.focused_id]);
+                    } else if let Some(focused_index) = self.get_focused_index() {
+                      //only windows have a menu
+                      let focused_info = &self.window_infos[focused_index];
+                      if !focused_info.menu.is_empty() {
+                        self.menu = Some(OpenMenu::new(focused_info.id, focused_info.menu.clone()));
+                        press_response = WindowMessageResponse::JustRedraw;
+                        redraw_ids = Some(vec![self.focused_id]);
+                      }
+                    }
+                  },
+                  &ShortcutType::Overview => {
+                    if self.overview.take().is_none() {
+                      self.toggle_start_menu(true);
+                      self.close_launcher();
+                      self.menu = None;
+                      self.hints = None;
+                      self.overview = Some(self.current_workspace);
+                    }
+                    press_response = WindowMessageResponse::JustRedraw;
+                  },
+                  &ShortcutType::WindowHints => {
+                    if self.hints.take().is_none() {
+                      self.toggle_start_menu(true);
+                      self.close_launcher();
+                      self.menu = None;
+                      //letters go left to right, top to bottom
+                      let mut windows: Vec<(Point, usize)> = self.get_windows_in_workspace(false).iter().map(|w| (w.top_left, w.id)).collect();
+                      windows.sort_by(|a, b| (a.0[1], a.0[0]).cmp(&(b.0[1], b.0[0])));
+                      if windows.len() > 0 {
+                        self.hints = Some(HINT_LETTERS.chars().zip(windows.into_iter().map(|w| w.1)).collect());
+                      }
+                    }
+                    press_response = WindowMessageResponse::JustRedraw;
+                  },
+                  &ShortcutType::ToggleAlwaysOnTop => {
+                    if let Some(focused_index) = self.get_focused_index() {
+                      if self.window_infos[focused_index].window_like.subtype() == WindowLikeType::Window {
+                        self.window_infos[focused_index].always_on_top ^= true;
+                        self.raise_always_on_top();
+                        press_response = WindowMessageResponse::JustRedraw;
+                      }
+                    }
+                  },
+                  &ShortcutType::ToggleScratchpad => {
+                    self.toggle_start_menu(true);
+                    self.close_launcher();
+                    if self.toggle_scratchpad() {
+                      self.taskbar_update_windows();
+                      press_response = WindowMessageResponse::JustRedraw;
+                    }
+                  },
+                  &ShortcutType::MoveWindowToScratchpad => {
+                    if let Some(focused_index) = self.get_focused_index() {
+                      if self.window_infos[focused_index].window_like.subtype() == WindowLikeType::Window {
+                        let window_info = &mut self.window_infos[focused_index];
+                        window_info.scratchpad = true;
+                        window_info.workspace = Workspace::Scratchpad;
+                        self.focus_top_window();
+                        self.taskbar_update_windows();
                         press_response = WindowMessageResponse::JustRedraw;
                       }
                     }
@@ -511,12 +1173,13 @@ impl WindowManager {
                     }
                   },
                   &ShortcutType::ClipboardPaste(_) => {
-                    if let Some(focused_index) = self.get_focused_index() {
-                      let window_like = &self.window_infos[focused_index].window_like;
-                      if window_like.subtype() == WindowLikeType::Window && self.clipboard.is_some() {
-                        press_response = self.window_infos[focused_index].window_like.handle_message(WindowMessage::Shortcut(ShortcutType::ClipboardPaste(self.clipboard.clone().unwrap())));
-                      }
+                    press_response = self.paste_latest();
+                  },
+                  &ShortcutType::ClipboardHistory => {
+                    if self.clipboard_picker.take().is_none() && !self.clipboard.entries.is_empty() {
+                      self.clipboard_picker = Some(0);
                     }
+                    press_response = WindowMessageResponse::JustRedraw;
                   },
                 };
               }
@@ -526,20 +1189,39 @@ impl WindowManager {
           KeyChar::Press(c) | KeyChar::Ctrl(c) => {
             let mut press_response = WindowMessageResponse::DoNothing;
             //send to focused window
-            if let Some(focused_index) = self.get_focused_index() {
-              press_response = self.window_infos[focused_index].window_like.handle_message(if key_char == KeyChar::Press(c) {
-                WindowMessage::KeyPress(KeyPress {
-                  key: c,
-                })
+            if self.clipboard_picker.is_some() {
+              press_response = self.clipboard_picker_key_press(key_char);
+            } else if self.overview.is_some() {
+              press_response = self.overview_key_press(key_char);
+            } else if self.hints.is_some() {
+              //labels are over every window, so redraw all
+              press_response = self.hint_key_press(key_char);
+            } else if let Some(focused_index) = self.get_focused_index() {
+              press_response = if self.menu.is_some() {
+                //the open menu bar gets key presses instead
+                self.menu_key_press(key_char)
               } else {
-                WindowMessage::CtrlKeyPress(KeyPress {
-                  key: c,
-                })
-              });
+                let response = self.window_infos[focused_index].window_like.handle_message(if key_char == KeyChar::Press(c) {
+                  WindowMessage::KeyPress(KeyPress {
+                    key: c,
+                  })
+                } else {
+                  WindowMessage::CtrlKeyPress(KeyPress {
+                    key: c,
+                  })
+                });
+                //if the window knows what it changed, only that needs to go to the screen
+                if response == WindowMessageResponse::JustRedraw {
+                  let window_info = &self.window_infos[focused_index];
+                  self.damage = window_info.window_like.damage().map(|rects| (window_info.id, rects));
+                }
+                response
+              };
               //at most, only the focused window needs to be redrawed
               redraw_ids = Some(vec![self.window_infos[focused_index].id]);
               //requests can result in window openings and closings, etc
-              if press_response != WindowMessageResponse::JustRedraw {
+              //and other windows of the same process may have changed too
+              if press_response != WindowMessageResponse::JustRedraw || self.shares_process(focused_index) {
                 redraw_ids = None;
               }
             }
@@ -548,6 +1230,8 @@ impl WindowManager {
         }
       },
       WindowManagerMessage::Touch(x, y) => {
+        //touches are in pixels
+        let (x, y) = ((x as f32 / self.config.scale) as usize, (y as f32 / self.config.scale) as usize);
         if x < 100 && y < 100 {
           //toggle onscreen keyboard if top left keyboard clicked
           if self.osk.is_some() {
@@ -579,8 +1263,21 @@ impl WindowManager {
         WindowMessageResponse::Request(request) => self.handle_request(request),
         _ => {},
       };
+      //after the request, since eg: a closed dialog gives its result to the window it was for
+      if let Some(focused_index) = self.get_focused_index() {
+        if self.refresh_titles(focused_index) {
+          //the title is in the window top, which the window doesn't know about
+          self.damage = None;
+          //taskbar needs to be redrawn too
+          if let Some(ids) = redraw_ids.as_mut() {
+            if let Some(taskbar) = self.window_infos.iter().find(|w| w.window_like.subtype() == WindowLikeType::Taskbar) {
+              ids.push(taskbar.id);
+            }
+          }
+        }
+      }
       if !is_key_char_request {
-        self.draw(redraw_ids, use_saved_buffer);
+        self.draw(redraw_ids);
       }
     }
   }
@@ -594,21 +1291,27 @@ impl WindowManager {
     match request {
       WindowManagerRequest::OpenWindow(w) => {
         let subtype = subtype.unwrap();
-        if subtype != WindowLikeType::Taskbar && subtype != WindowLikeType::StartMenu {
+        if subtype != WindowLikeType::Taskbar && subtype != WindowLikeType::StartMenu && subtype != WindowLikeType::Launcher {
           return;
         }
+        let mut process = None;
         let w: Option<WindowBox> = match w.as_str() {
           "StartMenu" => Some(Box::new(StartMenu::new())),
           "About" => Some(Box::new(About::new(self.version.clone()))),
           "Help" => Some(Box::new(Help::new())),
-          _ => Some(Box::new(ProxyWindowLike::new(&w))),
+          _ => {
+            let proxy = ProxyWindowLike::new(&w);
+            process = Some(Rc::clone(proxy.process()));
+            Some(Box::new(proxy))
+          },
         };
         if w.is_none() {
           return;
         }
         let w = w.unwrap();
-        //close start menu if open
+        //close start menu or launcher if open
         self.toggle_start_menu(true);
+        self.close_launcher();
         let ideal_dimensions = w.ideal_dimensions(self.dimensions);
         let top_left = match w.subtype() {
           WindowLikeType::StartMenu => [0, self.dimensions[1] - TASKBAR_HEIGHT - ideal_dimensions[1]],
@@ -616,8 +1319,37 @@ impl WindowManager {
           _ => [0, 0],
         };
         self.add_window_like(w, top_left, Some(ideal_dimensions));
+        if let Some(process) = process {
+          self.set_process(self.id_count, process, 0);
+        }
         self.taskbar_update_windows();
       },
+      WindowManagerRequest::OpenAdditionalWindow(window_id) => {
+        //0 is the first window
+        if subtype.unwrap() != WindowLikeType::Window || window_id == 0 {
+          return;
+        }
+        let focused_index = self.get_focused_index().unwrap();
+        let focused_info = &self.window_infos[focused_index];
+        if let Some((process, _)) = &focused_info.process {
+          let process = Rc::clone(process);
+          let top_left = [focused_info.top_left[0] + WINDOW_TOP_HEIGHT, focused_info.top_left[1] + WINDOW_TOP_HEIGHT];
+          if let Some(existing_index) = self.window_infos.iter().position(|w| w.process.as_ref().is_some_and(|(p, id)| Rc::ptr_eq(p, &process) && *id == window_id)) {
+            //already open, so bring it here
+            if self.window_infos[existing_index].workspace != Workspace::All {
+              self.window_infos[existing_index].workspace = Workspace::Workspace(self.current_workspace);
+            }
+            self.focused_id = self.window_infos[existing_index].id;
+            self.move_index_to_top(existing_index);
+          } else {
+            let w = ProxyWindowLike::new_additional(&process, window_id);
+            let ideal_dimensions = w.ideal_dimensions(self.dimensions);
+            self.add_window_like(Box::new(w), top_left, Some(ideal_dimensions));
+            self.set_process(self.id_count, process, window_id);
+          }
+          self.taskbar_update_windows();
+        }
+      },
       WindowManagerRequest::CloseStartMenu => {
         let subtype = subtype.unwrap();
         if subtype != WindowLikeType::Taskbar && subtype != WindowLikeType::StartMenu {
@@ -628,6 +1360,28 @@ impl WindowManager {
           self.window_infos.remove(start_menu_index);
         }
       },
+      WindowManagerRequest::CloseLauncher => {
+        if subtype.unwrap() != WindowLikeType::Launcher {
+          return;
+        }
+        self.close_launcher();
+      },
+      WindowManagerRequest::Dialog(dialog_type) => {
+        //one dialog at a time
+        if subtype.unwrap() != WindowLikeType::Window || self.dialog_for.is_some() {
+          return;
+        }
+        self.open_dialog(dialog_type, DialogFor::Window(self.focused_id));
+      },
+      WindowManagerRequest::CloseDialog(result) => {
+        if subtype.unwrap() != WindowLikeType::Dialog {
+          return;
+        }
+        self.close_dialog(result);
+      },
+      WindowManagerRequest::DenyClose | WindowManagerRequest::ConfirmClose(_) => {
+        //only meaningful as a response to a close request, which is handled there
+      },
       WindowManagerRequest::Unlock => {
         if subtype.unwrap() != WindowLikeType::LockScreen {
           return;
@@ -641,7 +1395,8 @@ impl WindowManager {
         self.lock();
       },
       WindowManagerRequest::ClipboardCopy(content) => {
-        self.clipboard = Some(content);
+        self.external_clipboard.write(&content);
+        self.clipboard.copy(content);
       },
       WindowManagerRequest::DoKeyChar(kc) => {
         self.handle_message(WindowManagerMessage::KeyChar(kc));
@@ -649,24 +1404,217 @@ impl WindowManager {
     };
   }
 
-  fn get_true_top_left(top_left: &Point, is_window: bool) -> Point {
-    [top_left[0], top_left[1] + if is_window { WINDOW_TOP_HEIGHT } else { 0 }]
+  fn get_true_top_left(top_left: &Point, content_top: usize) -> Point {
+    [top_left[0], top_left[1] + content_top]
   }
 
-  fn prevent_overflow_dimensions(dimensions: Dimensions, window_dimensions: Dimensions, top_left: Point) -> Dimensions {
-    [
-      min(dimensions[0], window_dimensions[0] - top_left[0]),
-      min(dimensions[1], window_dimensions[1] - top_left[1]),
-    ]
+  //another issue with a huge vector of draw instructions; it takes up heap memory
+  //draw the window (and its decorations if it is a window) to its own buffer
+  //the window's own drawing is retained, and only asked for and rasterised again if it changed.
+  //decorations and the menu bar go over it every time, since they depend on the window manager's state
+  fn render_window(&self, window_info: &WindowLikeInfo, is_window: bool, theme_info: &ThemeInfo) -> (Dimensions, FramebufferWriter) {
+    let window_dimensions = window_info.dimensions;
+    let mut framebuffer_info = self.writer.borrow().get_info();
+    //in pixels, like our writer
+    let window_width = self.writer.borrow().scaled(window_dimensions[0]);
+    let window_height = self.writer.borrow().scaled(window_dimensions[1]);
+    framebuffer_info.width = window_width;
+    framebuffer_info.height = window_height;
+    framebuffer_info.stride = window_width;
+    framebuffer_info.byte_len = window_width * window_height * framebuffer_info.bytes_per_pixel;
+    //make a writer just for the window
+    let mut window_writer: FramebufferWriter = FramebufferWriter::new(self.grayscale);
+    window_writer.init(framebuffer_info.clone());
+    window_writer.set_scale(self.config.scale);
+    if let Some(surface) = window_info.window_like.surface(window_dimensions) {
+      window_writer.draw_buffer([0, 0], window_height, window_width * framebuffer_info.bytes_per_pixel, &surface);
+    } else {
+      let mut instructions = VecDeque::from(window_info.window_like.draw(theme_info));
+      if is_window {
+        //offset top left by the window top height (and menu bar) for windows (because windows can't draw in that region)
+        let content_top = window_info.top_height();
+        instructions = instructions.iter().map(|instruction| {
+          match instruction {
+            DrawInstructions::Rect(top_left, dimensions, color) => DrawInstructions::Rect(WindowManager::get_true_top_left(top_left, content_top), *dimensions, *color),
+            DrawInstructions::Circle(centre, radius, color) => DrawInstructions::Circle(WindowManager::get_true_top_left(centre, content_top), *radius, *color),
+            DrawInstructions::AntiAliasedCircle(centre, radius, color) => DrawInstructions::AntiAliasedCircle(WindowManager::get_true_top_left(centre, content_top), *radius, *color),
+            DrawInstructions::Text(top_left, fonts, text, color, bg_color, horiz_spacing, mono_width) => DrawInstructions::Text(WindowManager::get_true_top_left(top_left, content_top), fonts.clone(), text.clone(), *color, *bg_color, *horiz_spacing, *mono_width),
+            DrawInstructions::Bmp(top_left, path, reverse) => DrawInstructions::Bmp(WindowManager::get_true_top_left(top_left, content_top), path.to_string(), *reverse),
+            DrawInstructions::TransparentBmp(top_left, path, reverse) => DrawInstructions::TransparentBmp(WindowManager::get_true_top_left(top_left, content_top), path.to_string(), *reverse),
+            DrawInstructions::Gradient(top_left, dimensions, start_color, end_color, steps) => DrawInstructions::Gradient(WindowManager::get_true_top_left(top_left, content_top), *dimensions, *start_color, *end_color, *steps),
+            DrawInstructions::Line(start, end, width, color) => DrawInstructions::Line(WindowManager::get_true_top_left(start, content_top), WindowManager::get_true_top_left(end, content_top), *width, *color),
+            DrawInstructions::AntiAliasedLine(start, end, width, color) => DrawInstructions::AntiAliasedLine(WindowManager::get_true_top_left(start, content_top), WindowManager::get_true_top_left(end, content_top), *width, *color),
+            DrawInstructions::RectOutline(top_left, dimensions, border_width, color) => DrawInstructions::RectOutline(WindowManager::get_true_top_left(top_left, content_top), *dimensions, *border_width, *color),
+            DrawInstructions::RoundedRect(top_left, dimensions, radius, color) => DrawInstructions::RoundedRect(WindowManager::get_true_top_left(top_left, content_top), *dimensions, *radius, *color),
+            DrawInstructions::Polygon(points, color) => DrawInstructions::Polygon(points.iter().map(|p| WindowManager::get_true_top_left(p, content_top)).collect(), *color),
+            DrawInstructions::PolygonOutline(points, width, color) => DrawInstructions::PolygonOutline(points.iter().map(|p| WindowManager::get_true_top_left(p, content_top)).collect(), *width, *color),
+            DrawInstructions::Arc(centre, radius, start_angle, end_angle, width, color) => DrawInstructions::Arc(WindowManager::get_true_top_left(centre, content_top), *radius, *start_angle, *end_angle, *width, *color),
+            DrawInstructions::Ellipse(centre, radii, color) => DrawInstructions::Ellipse(WindowManager::get_true_top_left(centre, content_top), *radii, *color),
+            DrawInstructions::CircleOutline(centre, radius, width, color) => DrawInstructions::CircleOutline(WindowManager::get_true_top_left(centre, content_top), *radius, *width, *color),
+            DrawInstructions::HorizontalGradient(top_left, dimensions, start_color, end_color, steps) => DrawInstructions::HorizontalGradient(WindowManager::get_true_top_left(top_left, content_top), *dimensions, *start_color, *end_color, *steps),
+            DrawInstructions::TranslucentRect(top_left, dimensions, color) => DrawInstructions::TranslucentRect(WindowManager::get_true_top_left(top_left, content_top), *dimensions, *color),
+            DrawInstructions::PushClip(top_left, dimensions) => DrawInstructions::PushClip(WindowManager::get_true_top_left(top_left, content_top), *dimensions),
+            DrawInstructions::PopClip => DrawInstructions::PopClip,
+          }
+        }).collect();
+        //draw window background
+        instructions.push_front(DrawInstructions::Rect([0, 0], window_dimensions, theme_info.background));
+      }
+      WindowManager::draw_instructions(&mut window_writer, instructions);
+      window_info.window_like.set_surface(window_dimensions, window_writer.get_buffer().to_vec());
+    }
+    if is_window {
+      //draw window top decorations and what not
+      let mut title = window_info.title.clone();
+      if window_info.workspace == Workspace::All {
+        title += " [sticky]";
+      }
+      if window_info.always_on_top {
+        title += " [on top]";
+      }
+      let title = trunc_words(&["nimbus-roman".to_string()], title, None, window_dimensions[0] - ICON_SIZE - 8);
+      let mut instructions = vec![
+        //left top border
+        DrawInstructions::Rect([0, 0], [window_dimensions[0], 1], theme_info.border_left_top),
+        DrawInstructions::Rect([0, 0], [1, window_dimensions[1]], theme_info.border_left_top),
+        //top
+        DrawInstructions::Rect([1, 1], [window_dimensions[0] - 2, WINDOW_TOP_HEIGHT - 3], theme_info.top),
+        //window icon and title
+        DrawInstructions::TransparentBmp([4, (WINDOW_TOP_HEIGHT - 2 - ICON_SIZE) / 2], window_info.icon.clone().unwrap_or_default(), true),
+        DrawInstructions::Text([ICON_SIZE + 8, 4], vec!["nimbus-roman".to_string()], title, theme_info.top_text, theme_info.top, None, None),
+        //top bottom border
+        DrawInstructions::Rect([1, WINDOW_TOP_HEIGHT - 2], [window_dimensions[0] - 2, 2], theme_info.border_left_top),
+        //right bottom border
+        DrawInstructions::Rect([window_dimensions[0] - 1, 1], [1, window_dimensions[1] - 1], theme_info.border_right_bottom),
+        DrawInstructions::Rect([1, window_dimensions[1] - 1], [window_dimensions[0] - 1, 1], theme_info.border_right_bottom),
+      ];
+      if let Some(menu) = self.menu.as_ref().filter(|m| m.id == window_info.id) {
+        instructions.extend(menu.draw(window_dimensions, WINDOW_TOP_HEIGHT, theme_info));
+      } else if !window_info.menu.is_empty() {
+        instructions.extend(menu::draw_bar(&window_info.menu, None, window_dimensions, WINDOW_TOP_HEIGHT, theme_info).0);
+      }
+      WindowManager::draw_instructions(&mut window_writer, instructions);
+    }
+    (window_dimensions, window_writer)
   }
 
-  //another issue with a huge vector of draw instructions; it takes up heap memory
-  pub fn draw(&mut self, maybe_redraw_ids: Option<Vec<usize>>, use_saved_buffer: bool) {
+  //the writer clips everything to the window, and to any clip rects the instructions push
+  fn draw_instructions(window_writer: &mut FramebufferWriter, instructions: impl IntoIterator<Item = DrawInstructions>) {
+    for instruction in instructions {
+      //unsafe { SERIAL1.lock().write_text(&format!("{:?}\n", instruction)); }
+      match instruction {
+        DrawInstructions::Rect(top_left, dimensions, color) => {
+          window_writer.draw_rect(top_left, dimensions, color);
+        },
+        DrawInstructions::Circle(centre, radius, color) => {
+          window_writer.draw_circle(centre, radius, color);
+        },
+        DrawInstructions::AntiAliasedCircle(centre, radius, color) => {
+          window_writer.draw_circle_anti_aliased(centre, radius, color);
+        },
+        DrawInstructions::Text(top_left, fonts, text, color, bg_color, horiz_spacing, mono_width) => {
+          window_writer.draw_text(top_left, fonts, &text, color, bg_color, horiz_spacing.unwrap_or(1), mono_width);
+        },
+        DrawInstructions::Bmp(top_left, path, reverse) => {
+          window_writer.draw_bmp(top_left, path, reverse, false);
+        },
+        DrawInstructions::TransparentBmp(top_left, path, reverse) => {
+          window_writer.draw_bmp(top_left, path, reverse, true);
+        },
+        DrawInstructions::Gradient(top_left, dimensions, start_color, end_color, steps) => {
+          window_writer.draw_gradient(top_left, dimensions, start_color, end_color, steps);
+        },
+        DrawInstructions::Line(start, end, width, color) => {
+          window_writer.draw_line(start, end, width, color);
+        },
+        DrawInstructions::AntiAliasedLine(start, end, width, color) => {
+          window_writer.draw_line_anti_aliased(start, end, width, color);
+        },
+        DrawInstructions::RectOutline(top_left, dimensions, border_width, color) => {
+          window_writer.draw_rect_outline(top_left, dimensions, border_width, color);
+        },
+        DrawInstructions::RoundedRect(top_left, dimensions, radius, color) => {
+          window_writer.draw_rounded_rect(top_left, dimensions, radius, color);
+        },
+        DrawInstructions::Polygon(points, color) => {
+          window_writer.draw_polygon(&points, color);
+        },
+        DrawInstructions::PolygonOutline(points, width, color) => {
+          window_writer.draw_polygon_outline(&points, width, color);
+        },
+        DrawInstructions::Arc(centre, radius, start_angle, end_angle, width, color) => {
+          window_writer.draw_arc(centre, radius, start_angle, end_angle, width, color);
+        },
+        DrawInstructions::Ellipse(centre, radii, color) => {
+          window_writer.draw_ellipse(centre, radii, color);
+        },
+        DrawInstructions::CircleOutline(centre, radius, width, color) => {
+          window_writer.draw_circle_outline(centre, radius, width, color);
+        },
+        DrawInstructions::HorizontalGradient(top_left, dimensions, start_color, end_color, steps) => {
+          window_writer.draw_horizontal_gradient(top_left, dimensions, start_color, end_color, steps);
+        },
+        DrawInstructions::TranslucentRect(top_left, dimensions, color) => {
+          window_writer.draw_translucent_rect(top_left, dimensions, color);
+        },
+        DrawInstructions::PushClip(top_left, dimensions) => {
+          window_writer.push_clip(top_left, dimensions);
+        },
+        DrawInstructions::PopClip => {
+          window_writer.pop_clip();
+        },
+      }
+    }
+    //so unpopped clips don't affect whatever is drawn next
+    window_writer.clear_clips();
+  }
+
+  //scaled down windows of each workspace in a 3x3 grid, instead of the usual
+  fn draw_overview(&self, selected: u8, theme_info: &ThemeInfo) {
+    let cell = [self.dimensions[0] / 3, self.dimensions[1] / 3];
+    //thumbnails keep the aspect ratio of the screen
+    let max_thumb = [cell[0] - OVERVIEW_PADDING * 2, cell[1] - OVERVIEW_PADDING * 3 - OVERVIEW_LABEL_HEIGHT];
+    let thumb = if max_thumb[0] * self.dimensions[1] > max_thumb[1] * self.dimensions[0] {
+      [max_thumb[1] * self.dimensions[0] / self.dimensions[1], max_thumb[1]]
+    } else {
+      [max_thumb[0], max_thumb[0] * self.dimensions[1] / self.dimensions[0]]
+    };
+    self.writer.borrow_mut().draw_rect([0, 0], self.dimensions, theme_info.background);
+    for workspace in 0..9 {
+      let cell_top_left = [(workspace as usize % 3) * cell[0], (workspace as usize / 3) * cell[1]];
+      let thumb_top_left = [cell_top_left[0] + (cell[0] - thumb[0]) / 2, cell_top_left[1] + OVERVIEW_PADDING];
+      let thumb_bottom_right = [thumb_top_left[0] + thumb[0], thumb_top_left[1] + thumb[1]];
+      let windows: Vec<&WindowLikeInfo> = self.window_infos.iter().filter(|w| w.window_like.subtype() == WindowLikeType::Window && w.in_workspace(workspace)).collect();
+      let mut label = format!("Workspace {}: {} window{}", workspace + 1, windows.len(), if windows.len() == 1 { "" } else { "s" });
+      if workspace == self.current_workspace {
+        label += " (current)";
+      }
+      {
+        let mut writer = self.writer.borrow_mut();
+        if workspace == selected {
+          writer.draw_rect([thumb_top_left[0] - 4, thumb_top_left[1] - 4], [thumb[0] + 8, thumb[1] + 8], theme_info.top);
+        }
+        writer.draw_rect(thumb_top_left, thumb, theme_info.alt_background);
+        writer.draw_text([thumb_top_left[0], thumb_bottom_right[1] + OVERVIEW_PADDING / 2], vec!["nimbus-roman".to_string()], &label, theme_info.text, theme_info.background, 1, None);
+      }
+      for window_info in windows {
+        let (window_dimensions, mut window_writer) = self.render_window(window_info, true, theme_info);
+        let top_left = [thumb_top_left[0] + window_info.top_left[0] * thumb[0] / self.dimensions[0], thumb_top_left[1] + window_info.top_left[1] * thumb[1] / self.dimensions[1]];
+        let dimensions = [(window_dimensions[0] * thumb[0] / self.dimensions[0]).max(1), (window_dimensions[1] * thumb[1] / self.dimensions[1]).max(1)];
+        let info = window_writer.get_info();
+        self.writer.borrow_mut().draw_buffer_scaled(top_left, dimensions, thumb_bottom_right, [info.width, info.height], window_writer.get_buffer());
+      }
+    }
+  }
+
+  #[allow(clippy::unwrap_or_default)]
+  pub fn draw(&mut self, maybe_redraw_ids: Option<Vec<usize>>) {
     let theme_info = get_theme_info(&self.theme).unwrap();
-    //use in conjunction with redraw ids, so a window moving can work without redrawing everything,
-    //can just redraw the saved state + window
-    if use_saved_buffer {
-      self.writer.borrow_mut().write_saved_buffer_to_raw();
+    let damage = self.damage.take();
+    if let Some(selected) = self.overview {
+      self.draw_overview(selected, &theme_info);
+      self.write_frame(None);
+      return;
     }
     //get windows to redraw
     let redraw_ids = maybe_redraw_ids.unwrap_or(Vec::new());
@@ -674,103 +1622,555 @@ impl WindowManager {
     if let Some(osk) = &self.osk {
       all_in_workspace.push(osk);
     }
-    let maybe_length = all_in_workspace.len();
     let redraw_windows = all_in_workspace.iter().filter(|w| {
       //basically, maybe_redraw_ids was None
       if redraw_ids.len() > 0 {
-        redraw_ids.contains(&w.id) || w.window_like.subtype() == WindowLikeType::OnscreenKeyboard
+        redraw_ids.contains(&w.id) || w.always_on_top || w.window_like.subtype() == WindowLikeType::OnscreenKeyboard
       } else {
         true
       }
     });
-    //these are needed to decide when to snapshot
-    let max_index = if redraw_ids.len() > 0 { redraw_ids.len() } else { maybe_length } - 1;
-    for (w_index, window_info) in redraw_windows.enumerate() {
-      let window_dimensions = if window_info.fullscreen {
-        [self.dimensions[0], self.dimensions[1] - TASKBAR_HEIGHT - INDICATOR_HEIGHT]
-      } else {
-        window_info.dimensions
-      };
-      let mut instructions = VecDeque::from(window_info.window_like.draw(&theme_info));
+    //parts of the screen that changed, if not the whole screen
+    let mut screen_damage: Option<Vec<Rect>> = if redraw_ids.len() > 0 { Some(Vec::new()) } else { None };
+    for window_info in redraw_windows {
       let is_window = window_info.window_like.subtype() == WindowLikeType::Window;
-      if is_window {
-        //if this is the top most window to draw, snapshot
-        if w_index == max_index && !use_saved_buffer && redraw_ids.len() == 0 {
-          self.writer.borrow_mut().save_buffer();
+      let (window_dimensions, mut window_writer) = self.render_window(window_info, is_window, &theme_info);
+      let window_damage = damage.as_ref().filter(|d| d.0 == window_info.id && screen_damage.is_some());
+      if let Some((_, rects)) = window_damage {
+        //the rects are relative to under the window top
+        let content_top = if is_window { window_info.top_height() } else { 0 };
+        for rect in rects {
+          //and may be outside of the window
+          let top_left = [min(rect.0[0], window_dimensions[0]), min(rect.0[1] + content_top, window_dimensions[1])];
+          let dimensions = [min(rect.1[0], window_dimensions[0] - top_left[0]), min(rect.1[1], window_dimensions[1] - top_left[1])];
+          let src_width = window_writer.get_info().width;
+          self.writer.borrow_mut().draw_buffer_region(window_info.top_left, src_width, (top_left, dimensions), window_writer.get_buffer());
+          screen_damage.as_mut().unwrap().push(([window_info.top_left[0] + top_left[0], window_info.top_left[1] + top_left[1]], dimensions));
+        }
+      } else {
+        let info = window_writer.get_info();
+        self.writer.borrow_mut().draw_buffer(window_info.top_left, info.height, info.width * info.bytes_per_pixel, window_writer.get_buffer());
+        if let Some(screen_damage) = screen_damage.as_mut() {
+          screen_damage.push((window_info.top_left, window_dimensions));
         }
-        //offset top left by the window top height for windows (because windows can't draw in that region)
-        instructions = instructions.iter().map(|instruction| {
-          match instruction {
-            DrawInstructions::Rect(top_left, dimensions, color) => DrawInstructions::Rect(WindowManager::get_true_top_left(top_left, is_window), *dimensions, *color),
-            DrawInstructions::Circle(centre, radius, color) => DrawInstructions::Circle(WindowManager::get_true_top_left(centre, is_window), *radius, *color),
-            DrawInstructions::Text(top_left, fonts, text, color, bg_color, horiz_spacing, mono_width) => DrawInstructions::Text(WindowManager::get_true_top_left(top_left, is_window), fonts.clone(), text.clone(), *color, *bg_color, *horiz_spacing, *mono_width),
-            DrawInstructions::Bmp(top_left, path, reverse) => DrawInstructions::Bmp(WindowManager::get_true_top_left(top_left, is_window), path.to_string(), *reverse),
-            DrawInstructions::Gradient(top_left, dimensions, start_color, end_color, steps) => DrawInstructions::Gradient(WindowManager::get_true_top_left(top_left, is_window), *dimensions, *start_color, *end_color, *steps),
-            DrawInstructions::Line(start, end, width, color) => DrawInstructions::Line(WindowManager::get_true_top_left(start, is_window), WindowManager::get_true_top_left(end, is_window), *width, *color),
-          }
-        }).collect();
-        //draw window background
-        instructions.push_front(DrawInstructions::Rect([0, 0], window_dimensions, theme_info.background));
-        //draw window top decorations and what not
-        let title = trunc_words(&["nimbus-roman".to_string()], window_info.window_like.title(), None, window_dimensions[0]);
-        instructions.extend(vec![
-          //left top border
-          DrawInstructions::Rect([0, 0], [window_dimensions[0], 1], theme_info.border_left_top),
-          DrawInstructions::Rect([0, 0], [1, window_dimensions[1]], theme_info.border_left_top),
-          //top
-          DrawInstructions::Rect([1, 1], [window_dimensions[0] - 2, WINDOW_TOP_HEIGHT - 3], theme_info.top),
-          //window title
-          DrawInstructions::Text([4, 4], vec!["nimbus-roman".to_string()], title, theme_info.top_text, theme_info.top, None, None),
-          //top bottom border
-          DrawInstructions::Rect([1, WINDOW_TOP_HEIGHT - 2], [window_dimensions[0] - 2, 2], theme_info.border_left_top),
-          //right bottom border
-          DrawInstructions::Rect([window_dimensions[0] - 1, 1], [1, window_dimensions[1] - 1], theme_info.border_right_bottom),
-          DrawInstructions::Rect([1, window_dimensions[1] - 1], [window_dimensions[0] - 1, 1], theme_info.border_right_bottom),
-        ]);
       }
-      let mut framebuffer_info = self.writer.borrow().get_info();
-      let bytes_per_pixel = framebuffer_info.bytes_per_pixel;
-      let window_width = window_dimensions[0];
-      let window_height = window_dimensions[1];
-      framebuffer_info.width = window_width;
-      framebuffer_info.height = window_height;
-      framebuffer_info.stride = window_width;
-      framebuffer_info.byte_len = window_width * window_height * bytes_per_pixel;
-      //make a writer just for the window
-      let mut window_writer: FramebufferWriter = FramebufferWriter::new(self.grayscale);
-      window_writer.init(framebuffer_info);
-      for instruction in instructions {
-        //unsafe { SERIAL1.lock().write_text(&format!("{:?}\n", instruction)); }
-        match instruction {
-          DrawInstructions::Rect(top_left, dimensions, color) => {
-            //try and prevent overflows out of the window
-            let true_dimensions = WindowManager::prevent_overflow_dimensions(dimensions, window_dimensions, top_left);
-            window_writer.draw_rect(top_left, true_dimensions, color);
-          },
-          DrawInstructions::Circle(centre, radius, color) => {
-            window_writer.draw_circle(centre, radius, color);
-          },
-          DrawInstructions::Text(top_left, fonts, text, color, bg_color, horiz_spacing, mono_width) => {
-            //if overflow, won't draw, I think
-            window_writer.draw_text(top_left, fonts, &text, color, bg_color, horiz_spacing.unwrap_or(1), mono_width);
-          },
-          DrawInstructions::Bmp(top_left, path, reverse) => {
-            window_writer.draw_bmp(top_left, path, reverse);
-          },
-          DrawInstructions::Gradient(top_left, dimensions, start_color, end_color, steps) => {
-            let true_dimensions = WindowManager::prevent_overflow_dimensions(dimensions, window_dimensions, top_left);
-            window_writer.draw_gradient(top_left, true_dimensions, start_color, end_color, steps);
-          },
-          DrawInstructions::Line(start, end, width, color) => {
-            window_writer.draw_line(start, end, width, color);
-          },
+    }
+    //hint mode labels, centred over their windows
+    if let Some(hints) = &self.hints {
+      for (letter, id) in hints {
+        if let Some(window_info) = self.window_infos.iter().find(|w| w.id == *id) {
+          let window_dimensions = window_info.dimensions;
+          //windows can be partially off screen
+          let x = min(window_info.top_left[0] + window_dimensions[0] / 2, self.dimensions[0] - HINT_SIZE);
+          let y = min(window_info.top_left[1] + window_dimensions[1] / 2, self.dimensions[1] - HINT_SIZE);
+          let mut writer = self.writer.borrow_mut();
+          writer.draw_rect([x, y], [HINT_SIZE, HINT_SIZE], theme_info.top_text);
+          writer.draw_rect([x + 2, y + 2], [HINT_SIZE - 4, HINT_SIZE - 4], theme_info.top);
+          writer.draw_text([x + 7, y + 4], vec!["nimbus-romono".to_string()], &letter.to_string(), theme_info.top_text, theme_info.top, 0, Some(10));
         }
       }
-      self.writer.borrow_mut().draw_buffer(window_info.top_left, window_dimensions[1], window_dimensions[0] * bytes_per_pixel, &window_writer.get_buffer());
     }
-    //could probably figure out a way to do borrow() when self.rotate is false but does it matter?
+    //clipboard history picker, centred on screen
+    if let Some(selected) = self.clipboard_picker {
+      let width = min(500, self.dimensions[0]);
+      let rows = min(PICKER_MAX_ROWS, self.clipboard.entries.len());
+      let height = rows * PICKER_LINE_HEIGHT + 10;
+      let top_left = [(self.dimensions[0] - width) / 2, self.dimensions[1].saturating_sub(height) / 2];
+      //scroll so the selected entry is always visible
+      let first = if selected >= rows { selected - rows + 1 } else { 0 };
+      let fonts = vec!["nimbus-roman".to_string()];
+      let flags_width = 90;
+      let mut writer = self.writer.borrow_mut();
+      writer.draw_rect(top_left, [width, height], theme_info.border_right_bottom);
+      writer.draw_rect(top_left, [width - 1, height - 1], theme_info.border_left_top);
+      writer.draw_rect([top_left[0] + 1, top_left[1] + 1], [width - 2, height - 2], theme_info.background);
+      for (row, entry) in self.clipboard.entries.iter().skip(first).take(rows).enumerate() {
+        let y = top_left[1] + 5 + row * PICKER_LINE_HEIGHT;
+        let (text_color, bg_color) = if first + row == selected {
+          (theme_info.top_text, theme_info.top)
+        } else {
+          (theme_info.text, theme_info.background)
+        };
+        //only the first line, and sensitive entries are hidden
+        let text = if entry.sensitive { "********".to_string() } else { entry.content.lines().next().unwrap_or("").to_string() };
+        let flags = match (entry.pinned, entry.sensitive) {
+          (true, true) => "pinned, hidden",
+          (true, false) => "pinned",
+          (false, true) => "hidden",
+          (false, false) => "",
+        };
+        writer.draw_rect([top_left[0] + 5, y], [width - 10, PICKER_LINE_HEIGHT], bg_color);
+        writer.draw_text([top_left[0] + 10, y + 2], fonts.clone(), &trunc_words(&fonts, text, None, width - 20 - flags_width), text_color, bg_color, 1, None);
+        writer.draw_text([top_left[0] + width - 10 - flags_width, y + 2], fonts.clone(), flags, text_color, bg_color, 1, None);
+      }
+    }
+    self.write_frame(screen_damage);
+  }
+
+  //only the lines with damage, if there is any. rotated frames are always written whole
+  fn write_frame(&mut self, damage: Option<Vec<Rect>>) {
+    //could probably figure out a way to do borrow() when not rotated but does it matter?
     let mut writer_borrow = self.writer.borrow_mut();
-    let frame = if self.rotate { writer_borrow.get_transposed_buffer() } else { writer_borrow.get_buffer() };
-    self.framebuffer.write_frame(frame);
+    if let (Some(damage), Rotation::Deg0) = (damage, self.rotation) {
+      let ranges = writer_borrow.damaged_byte_ranges(&damage);
+      self.framebuffer.write_frame_regions(writer_borrow.get_buffer(), &ranges);
+    } else {
+      self.framebuffer.write_frame(writer_borrow.get_rotated_buffer(self.rotation));
+    }
+  }
+}
+
+#[cfg(test)]
+struct KeyCounter {
+  keys: Rc<RefCell<usize>>,
+}
+
+#[cfg(test)]
+impl WindowLike for KeyCounter {
+  fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
+    match message {
+      WindowMessage::KeyPress(_) => {
+        *self.keys.borrow_mut() += 1;
+        WindowMessageResponse::JustRedraw
+      },
+      _ => WindowMessageResponse::DoNothing,
+    }
+  }
+
+  fn draw(&self, _theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    Vec::new()
+  }
+
+  fn title(&self) -> String {
+    "Keys".to_string()
+  }
+
+  fn subtype(&self) -> WindowLikeType {
+    WindowLikeType::Window
+  }
+
+  fn ideal_dimensions(&self, _dimensions: Dimensions) -> Dimensions {
+    [100, 100]
+  }
+}
+
+//resizable, and records the dimensions it is given
+#[cfg(test)]
+struct SizedWindow {
+  dimensions: Rc<RefCell<Dimensions>>,
+  min: Option<Dimensions>,
+  max: Option<Dimensions>,
+}
+
+#[cfg(test)]
+impl WindowLike for SizedWindow {
+  fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
+    match message {
+      WindowMessage::Init(dimensions) | WindowMessage::ChangeDimensions(dimensions) => {
+        *self.dimensions.borrow_mut() = dimensions;
+        WindowMessageResponse::JustRedraw
+      },
+      _ => WindowMessageResponse::DoNothing,
+    }
+  }
+
+  fn draw(&self, _theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    Vec::new()
+  }
+
+  fn title(&self) -> String {
+    "Sized".to_string()
+  }
+
+  fn subtype(&self) -> WindowLikeType {
+    WindowLikeType::Window
+  }
+
+  fn ideal_dimensions(&self, _dimensions: Dimensions) -> Dimensions {
+    [100, 100]
+  }
+
+  fn resizable(&self) -> bool {
+    true
+  }
+
+  fn size_hints(&self) -> SizeHints {
+    SizeHints {
+      min: self.min,
+      max: self.max,
+      ..Default::default()
+    }
+  }
+}
+
+//answers close requests with whatever it is given
+#[cfg(test)]
+struct Closable {
+  close_response: fn() -> WindowMessageResponse,
+}
+
+#[cfg(test)]
+impl WindowLike for Closable {
+  fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
+    match message {
+      WindowMessage::CloseRequest => (self.close_response)(),
+      _ => WindowMessageResponse::DoNothing,
+    }
+  }
+
+  fn draw(&self, _theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    Vec::new()
+  }
+
+  fn title(&self) -> String {
+    "Closable".to_string()
   }
+
+  fn subtype(&self) -> WindowLikeType {
+    WindowLikeType::Window
+  }
+
+  fn ideal_dimensions(&self, _dimensions: Dimensions) -> Dimensions {
+    [100, 100]
+  }
+}
+
+//d asks for a name, and records the results. "again" asks again with a message dialog
+#[cfg(test)]
+struct DialogAsker {
+  keys: Rc<RefCell<usize>>,
+  results: Rc<RefCell<Vec<DialogResult>>>,
+}
+
+#[cfg(test)]
+impl WindowLike for DialogAsker {
+  fn handle_message(&mut self, message: WindowMessage) -> WindowMessageResponse {
+    match message {
+      WindowMessage::KeyPress(key_press) => {
+        *self.keys.borrow_mut() += 1;
+        if key_press.key == 'd' {
+          WindowMessageResponse::Request(WindowManagerRequest::Dialog(DialogType::Prompt("Name".to_string(), String::new())))
+        } else {
+          WindowMessageResponse::DoNothing
+        }
+      },
+      WindowMessage::DialogResult(result) => {
+        let again = result == DialogResult::Text("again".to_string());
+        self.results.borrow_mut().push(result);
+        if again {
+          WindowMessageResponse::Request(WindowManagerRequest::Dialog(DialogType::Message("Again".to_string())))
+        } else {
+          WindowMessageResponse::JustRedraw
+        }
+      },
+      _ => WindowMessageResponse::DoNothing,
+    }
+  }
+
+  fn draw(&self, _theme_info: &ThemeInfo) -> Vec<DrawInstructions> {
+    Vec::new()
+  }
+
+  fn title(&self) -> String {
+    "Dialogs".to_string()
+  }
+
+  fn subtype(&self) -> WindowLikeType {
+    WindowLikeType::Window
+  }
+
+  fn ideal_dimensions(&self, _dimensions: Dimensions) -> Dimensions {
+    [100, 100]
+  }
+}
+
+#[cfg(test)]
+fn test_window_manager(name: &str) -> WindowManager {
+  use crate::framebuffer::FramebufferInfo;
+
+  let (width, height) = (400, 300);
+  let path = std::env::temp_dir().join(format!("ming-wm-{}-{}", name, std::process::id()));
+  let framebuffer = Framebuffer::open_headless(&path.to_string_lossy(), width as u32, height as u32, 4).unwrap();
+  let _ = std::fs::remove_file(&path);
+  let mut writer = FramebufferWriter::new(false);
+  writer.init(FramebufferInfo {
+    byte_len: width * height * 4,
+    width,
+    height,
+    bytes_per_pixel: 4,
+    stride: width,
+    old_stride: None,
+    format: Default::default(),
+  });
+  let mut wm = WindowManager::new(writer, framebuffer, [width, height], Rotation::Deg0, false, String::new(), [0; 64]);
+  wm.unlock();
+  wm
+}
+
+#[test]
+fn hidden_scratchpad_window_loses_focus() {
+  let mut wm = test_window_manager("scratchpad-focus");
+  let keys = Rc::new(RefCell::new(0));
+  wm.add_window_like(Box::new(KeyCounter { keys: keys.clone() }), [0, INDICATOR_HEIGHT], None);
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('a')));
+  assert!(*keys.borrow() == 1);
+  //hide the only window, keys shouldn't go to it anymore
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('_')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('a')));
+  assert!(*keys.borrow() == 1);
+  //show it then hide it again, this time with the toggle
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('-')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('a')));
+  assert!(*keys.borrow() == 2);
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('-')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('a')));
+  assert!(*keys.borrow() == 2);
+}
+
+#[test]
+fn closing_launcher_refocuses() {
+  let mut wm = test_window_manager("launcher-focus");
+  //no windows, so focus goes back to the workspace indicator
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('d')));
+  let launcher_id = wm.focused_id;
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('𐘃')));
+  assert!(wm.focused_id != launcher_id && wm.get_focused_index().is_some());
+  //back to the window that was focused before
+  let keys = Rc::new(RefCell::new(0));
+  wm.add_window_like(Box::new(KeyCounter { keys: keys.clone() }), [0, INDICATOR_HEIGHT], None);
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('d')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('𐘃')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('a')));
+  assert!(*keys.borrow() == 1);
+}
+
+#[test]
+fn size_hints_apply_on_open_and_fullscreen() {
+  let mut wm = test_window_manager("size-hints");
+  let dimensions = Rc::new(RefCell::new([0, 0]));
+  let window = SizedWindow { dimensions: dimensions.clone(), min: Some([150, 120]), max: Some([300, 200]) };
+  wm.add_window_like(Box::new(window), [0, INDICATOR_HEIGHT], None);
+  //the ideal dimensions are below the min hint
+  assert!(*dimensions.borrow() == [150, 120]);
+  assert!(wm.window_infos[wm.get_focused_index().unwrap()].dimensions == [150, 120 + WINDOW_TOP_HEIGHT]);
+  //fullscreen stops at the max hint, and the frame is drawn around that, not the whole screen
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('f')));
+  let max_height = 300 - TASKBAR_HEIGHT - INDICATOR_HEIGHT - WINDOW_TOP_HEIGHT;
+  assert!(*dimensions.borrow() == [300, min(200, max_height)]);
+  assert!(wm.window_infos[wm.get_focused_index().unwrap()].dimensions == [300, min(200, max_height) + WINDOW_TOP_HEIGHT]);
+  //and back
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('f')));
+  assert!(*dimensions.borrow() == [150, 120]);
+  assert!(wm.window_infos[wm.get_focused_index().unwrap()].dimensions == [150, 120 + WINDOW_TOP_HEIGHT]);
+}
+
+#[test]
+fn moving_window_to_workspace_refocuses() {
+  let mut wm = test_window_manager("move-focus");
+  let bottom_keys = Rc::new(RefCell::new(0));
+  let top_keys = Rc::new(RefCell::new(0));
+  wm.add_window_like(Box::new(KeyCounter { keys: bottom_keys.clone() }), [0, INDICATOR_HEIGHT], None);
+  wm.add_window_like(Box::new(KeyCounter { keys: top_keys.clone() }), [0, INDICATOR_HEIGHT], None);
+  //with the shortcut, focus goes to the window under it
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('@')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('a')));
+  assert!(*bottom_keys.borrow() == 1 && *top_keys.borrow() == 0);
+  //from the overview, to the third workspace. no windows left, so nothing gets keys
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('z')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('3')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('m')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('𐘃')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('a')));
+  assert!(*bottom_keys.borrow() == 1 && *top_keys.borrow() == 0);
+}
+
+#[test]
+fn snap_presets_and_edge_resizing() {
+  let mut wm = test_window_manager("snap");
+  let dimensions = Rc::new(RefCell::new([0, 0]));
+  wm.add_window_like(Box::new(SizedWindow { dimensions: dimensions.clone(), min: None, max: None }), [10, INDICATOR_HEIGHT + 10], None);
+  let index = wm.get_focused_index().unwrap();
+  let (width, height) = (400, 300 - INDICATOR_HEIGHT - TASKBAR_HEIGHT);
+  //quarters, starting with the closest, then clockwise
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('a')));
+  assert!(wm.window_infos[index].top_left == [0, INDICATOR_HEIGHT]);
+  assert!(wm.window_infos[index].dimensions == [width / 2, height / 2]);
+  assert!(*dimensions.borrow() == [width / 2, height / 2 - WINDOW_TOP_HEIGHT]);
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('a')));
+  assert!(wm.window_infos[index].top_left == [width / 2, INDICATOR_HEIGHT]);
+  //two thirds, the right one is closer
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('W')));
+  assert!(wm.window_infos[index].top_left == [width - width / 3 * 2, INDICATOR_HEIGHT]);
+  assert!(wm.window_infos[index].dimensions == [width / 3 * 2, height]);
+  //maximize vertically keeps the width and x
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('y')));
+  assert!(wm.window_infos[index].top_left == [width - width / 3 * 2, INDICATOR_HEIGHT]);
+  assert!(wm.window_infos[index].dimensions == [width / 3 * 2, height]);
+  //shrinking from the left and top edges keeps the right and bottom edges in place
+  let step = wm.config.resize_step;
+  let content = *dimensions.borrow();
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('<')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('>')));
+  assert!(*dimensions.borrow() == [content[0] - step, content[1] - step]);
+  assert!(wm.window_infos[index].top_left == [width - width / 3 * 2 + step, INDICATOR_HEIGHT + step]);
+  //windows that aren't resizable aren't snapped
+  wm.add_window_like(Box::new(KeyCounter { keys: Rc::new(RefCell::new(0)) }), [10, INDICATOR_HEIGHT], None);
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('a')));
+  let index = wm.get_focused_index().unwrap();
+  assert!(wm.window_infos[index].top_left == [10, INDICATOR_HEIGHT]);
+}
+
+#[test]
+fn sticky_windows_are_in_every_workspace() {
+  let mut wm = test_window_manager("sticky");
+  wm.add_window_like(Box::new(KeyCounter { keys: Rc::new(RefCell::new(0)) }), [0, INDICATOR_HEIGHT], None);
+  let normal_id = wm.focused_id;
+  wm.add_window_like(Box::new(KeyCounter { keys: Rc::new(RefCell::new(0)) }), [0, INDICATOR_HEIGHT], None);
+  let sticky_id = wm.focused_id;
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('S')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('2')));
+  let ids: Vec<usize> = wm.get_windows_in_workspace(false).iter().map(|w| w.id).collect();
+  assert!(ids.contains(&sticky_id) && !ids.contains(&normal_id));
+  //and not sticky anymore, so it stays in the workspace it was unstuck in
+  wm.focused_id = sticky_id;
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('S')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('1')));
+  let ids: Vec<usize> = wm.get_windows_in_workspace(false).iter().map(|w| w.id).collect();
+  assert!(!ids.contains(&sticky_id) && ids.contains(&normal_id));
+}
+
+#[test]
+fn always_on_top_windows_stay_above() {
+  let mut wm = test_window_manager("on-top");
+  wm.add_window_like(Box::new(KeyCounter { keys: Rc::new(RefCell::new(0)) }), [0, INDICATOR_HEIGHT], None);
+  let on_top_id = wm.focused_id;
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('o')));
+  //new windows and focused windows go under it
+  wm.add_window_like(Box::new(KeyCounter { keys: Rc::new(RefCell::new(0)) }), [0, INDICATOR_HEIGHT], None);
+  let other_id = wm.focused_id;
+  assert!(wm.window_infos.last().unwrap().id == on_top_id);
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('[')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('[')));
+  assert!(wm.focused_id == other_id);
+  assert!(wm.window_infos.last().unwrap().id == on_top_id);
+  //turned off, so the focused window goes to the top like usual
+  wm.focused_id = on_top_id;
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('o')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('[')));
+  assert!(wm.focused_id == other_id);
+  assert!(wm.window_infos.last().unwrap().id == other_id);
+}
+
+#[test]
+fn close_negotiation() {
+  let mut wm = test_window_manager("close");
+  let is_open = |wm: &WindowManager, id: usize| wm.window_infos.iter().any(|w| w.id == id);
+  let has_dialog = |wm: &WindowManager| wm.window_infos.iter().any(|w| w.window_like.subtype() == WindowLikeType::Dialog);
+  //allowed
+  wm.add_window_like(Box::new(Closable { close_response: || WindowMessageResponse::DoNothing }), [0, INDICATOR_HEIGHT], None);
+  let id = wm.focused_id;
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('q')));
+  assert!(!is_open(&wm, id));
+  //denied, but force quit still works
+  wm.add_window_like(Box::new(Closable { close_response: || WindowMessageResponse::Request(WindowManagerRequest::DenyClose) }), [0, INDICATOR_HEIGHT], None);
+  let id = wm.focused_id;
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('q')));
+  assert!(is_open(&wm, id) && wm.focused_id == id);
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('Q')));
+  assert!(!is_open(&wm, id));
+  //asks the user first. no keeps it open and focused, yes closes it
+  wm.add_window_like(Box::new(Closable { close_response: || WindowMessageResponse::Request(WindowManagerRequest::ConfirmClose("Unsaved".to_string())) }), [0, INDICATOR_HEIGHT], None);
+  let id = wm.focused_id;
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('q')));
+  assert!(has_dialog(&wm) && wm.focused_id != id);
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('n')));
+  assert!(!has_dialog(&wm) && is_open(&wm, id) && wm.focused_id == id);
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('q')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('y')));
+  assert!(!has_dialog(&wm) && !is_open(&wm, id));
+  //force quit from the dialog closes both
+  wm.add_window_like(Box::new(Closable { close_response: || WindowMessageResponse::Request(WindowManagerRequest::ConfirmClose("Unsaved".to_string())) }), [0, INDICATOR_HEIGHT], None);
+  let id = wm.focused_id;
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('q')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('Q')));
+  assert!(!has_dialog(&wm) && !is_open(&wm, id));
+  assert!(wm.get_focused_index().is_some());
+}
+
+#[test]
+fn dialog_results_go_to_their_window() {
+  let mut wm = test_window_manager("dialog");
+  let keys = Rc::new(RefCell::new(0));
+  let results = Rc::new(RefCell::new(Vec::new()));
+  wm.add_window_like(Box::new(KeyCounter { keys: Rc::new(RefCell::new(0)) }), [0, INDICATOR_HEIGHT], None);
+  wm.add_window_like(Box::new(DialogAsker { keys: keys.clone(), results: results.clone() }), [0, INDICATOR_HEIGHT], None);
+  let id = wm.focused_id;
+  let type_text = |wm: &mut WindowManager, text: &str| {
+    for c in text.chars() {
+      wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press(c)));
+    }
+  };
+  type_text(&mut wm, "d");
+  //keys go to the dialog, and focus can't be moved away
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('[')));
+  type_text(&mut wm, "hi𐘂");
+  assert!(*keys.borrow() == 1);
+  assert!(*results.borrow() == vec![DialogResult::Text("hi".to_string())]);
+  assert!(wm.focused_id == id);
+  //cancelled
+  type_text(&mut wm, "d𐘃");
+  assert!(results.borrow().last() == Some(&DialogResult::Cancelled));
+  assert!(wm.focused_id == id);
+  //the window can open another dialog from the result
+  type_text(&mut wm, "dagain𐘂");
+  assert!(wm.window_infos.iter().any(|w| w.window_like.subtype() == WindowLikeType::Dialog));
+  type_text(&mut wm, "𐘂");
+  assert!(results.borrow().last() == Some(&DialogResult::Ok));
+  assert!(wm.focused_id == id && *keys.borrow() == 3);
+}
+
+#[test]
+fn window_hints() {
+  let mut wm = test_window_manager("hints");
+  let mut ids = Vec::new();
+  //added out of order
+  for top_left in [[200, 100], [0, 100], [150, INDICATOR_HEIGHT]] {
+    wm.add_window_like(Box::new(KeyCounter { keys: Rc::new(RefCell::new(0)) }), top_left, None);
+    ids.push(wm.focused_id);
+  }
+  //letters go left to right, top to bottom
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('g')));
+  assert!(wm.hints == Some(vec![('a', ids[2]), ('s', ids[1]), ('d', ids[0])]));
+  //lowercase focuses and raises
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('d')));
+  assert!(wm.hints.is_none());
+  assert!(wm.focused_id == ids[0] && wm.window_infos.last().unwrap().id == ids[0]);
+  //uppercase also swaps it with the focused window
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('g')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('S')));
+  assert!(wm.focused_id == ids[1]);
+  let top_left = |wm: &WindowManager, id: usize| wm.window_infos.iter().find(|w| w.id == id).unwrap().top_left;
+  assert!(top_left(&wm, ids[1]) == [200, 100] && top_left(&wm, ids[0]) == [0, 100]);
+  //anything else cancels
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('g')));
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('1')));
+  assert!(wm.hints.is_none() && wm.focused_id == ids[1]);
+}
+
+#[test]
+fn unchanged_windows_are_not_drawn_again() {
+  use crate::retained::DrawCounter;
+
+  let mut wm = test_window_manager("retained");
+  let draws = Rc::new(RefCell::new(0));
+  wm.add_window_like(Box::new(DrawCounter { draws: draws.clone() }), [0, INDICATOR_HEIGHT], None);
+  wm.draw(None);
+  assert!(*draws.borrow() == 1);
+  //moving is just copying the surface somewhere else
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Alt('l')));
+  //nothing changed
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('a')));
+  assert!(*draws.borrow() == 1);
+  wm.handle_message(WindowManagerMessage::KeyChar(KeyChar::Press('r')));
+  assert!(*draws.borrow() == 2);
 }